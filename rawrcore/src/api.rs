@@ -0,0 +1,98 @@
+use serde_json::Value;
+
+use crate::{
+    auth::scopes::Scope,
+    errors::RawrCoreError,
+    models::fullname::Fullname,
+    sessions::{RequestMethod, RetryStrategy, Session},
+};
+
+pub mod actions;
+pub mod automoderator;
+pub mod comments;
+pub mod flair;
+pub mod inbox;
+pub mod media;
+pub mod moderation;
+pub mod mod_notes;
+pub mod modlog;
+pub mod modmail;
+pub mod redditors;
+pub mod relationships;
+pub mod rules;
+pub mod search;
+pub mod settings;
+pub mod submissions;
+pub mod subreddits;
+pub mod wiki;
+
+// limits sorts like top and controversial to recent items
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeFilter {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+    All,
+}
+impl TimeFilter {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            TimeFilter::Hour => "hour",
+            TimeFilter::Day => "day",
+            TimeFilter::Week => "week",
+            TimeFilter::Month => "month",
+            TimeFilter::Year => "year",
+            TimeFilter::All => "all",
+        }
+    }
+}
+
+pub(crate) async fn post_form<R: RetryStrategy>(
+    session: &mut Session<R>,
+    path: &str,
+    data: &[(&str, String)],
+) -> Result<Value, RawrCoreError> {
+    session
+        .request::<[(&str, String)], (), (), ()>(
+            RequestMethod::Post,
+            path,
+            Some(data),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+}
+
+// for endpoints whose scope depends on what is posted; `scope` is always
+// checked, whatever the session's `check_scopes` is set to
+pub(crate) async fn post_form_with_scope<R: RetryStrategy>(
+    session: &mut Session<R>,
+    scope: Scope,
+    path: &str,
+    data: &[(&str, String)],
+) -> Result<Value, RawrCoreError> {
+    session
+        .request_with_scope::<[(&str, String)], (), (), ()>(
+            scope,
+            RequestMethod::Post,
+            path,
+            Some(data),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+}
+
+pub(crate) fn join_fullnames(fullnames: &[Fullname]) -> String {
+    fullnames
+        .iter()
+        .map(|fullname| fullname.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
@@ -0,0 +1,330 @@
+use serde::Deserialize;
+
+use super::{join_fullnames, post_form};
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    models::fullname::{Fullname, ThingKind},
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const HIDE_BATCH_SIZE: usize = 50;
+const MAX_REPORT_REASON_LENGTH: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoteDirection {
+    Up,
+    Down,
+    Clear,
+}
+impl VoteDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            VoteDirection::Up => "1",
+            VoteDirection::Down => "-1",
+            VoteDirection::Clear => "0",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum ReportReason {
+    // the short name of one of the subreddit's rules
+    Rule(String),
+    // one of reddit's site-wide rules
+    SiteRule(String),
+    Other(String),
+}
+impl ReportReason {
+    fn form_data(&self) -> [(&'static str, String); 2] {
+        match self {
+            ReportReason::Rule(reason) => [("reason", reason.clone()), ("rule_reason", reason.clone())],
+            ReportReason::SiteRule(reason) => [("reason", reason.clone()), ("site_reason", reason.clone())],
+            ReportReason::Other(reason) => [("reason", "other".to_string()), ("other_reason", reason.clone())],
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            ReportReason::Rule(reason) | ReportReason::SiteRule(reason) | ReportReason::Other(reason) => {
+                reason.as_str()
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SavedCategory {
+    category: String,
+}
+
+#[derive(Deserialize)]
+struct SavedCategories {
+    categories: Vec<SavedCategory>,
+}
+
+fn ensure_votable(fullname: &Fullname) -> Result<(), RawrCoreError> {
+    match fullname.kind() {
+        ThingKind::Link | ThingKind::Comment => Ok(()),
+        _ => Err(RawrCoreError::InvalidInvocation(
+            "only submissions and comments can be acted on",
+        )),
+    }
+}
+
+pub async fn vote<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    direction: VoteDirection,
+) -> Result<(), RawrCoreError> {
+    ensure_votable(fullname)?;
+
+    let data = [
+        ("id", fullname.to_string()),
+        ("dir", direction.as_str().to_string()),
+    ];
+    post_form(session, constants::VOTE_PATH.as_str(), &data).await?;
+
+    Ok(())
+}
+
+// categories are only available to reddit premium members
+pub async fn save<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    category: Option<&str>,
+) -> Result<(), RawrCoreError> {
+    ensure_votable(fullname)?;
+
+    let mut data = vec![("id", fullname.to_string())];
+
+    if let Some(category) = category {
+        data.push(("category", category.to_string()));
+    }
+
+    post_form(session, constants::SAVE_PATH.as_str(), &data).await?;
+
+    Ok(())
+}
+
+pub async fn unsave<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+) -> Result<(), RawrCoreError> {
+    ensure_votable(fullname)?;
+
+    let data = [("id", fullname.to_string())];
+    post_form(session, constants::UNSAVE_PATH.as_str(), &data).await?;
+
+    Ok(())
+}
+
+pub async fn saved_categories<R: RetryStrategy>(
+    session: &mut Session<R>,
+) -> Result<Vec<String>, RawrCoreError> {
+    let value = session
+        .request::<(), (), (), ()>(
+            RequestMethod::Get,
+            constants::SAVED_CATEGORIES_PATH.as_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    let saved = util::from_json_value::<SavedCategories>(value)?;
+
+    Ok(saved
+        .categories
+        .into_iter()
+        .map(|category| category.category)
+        .collect())
+}
+
+async fn set_hidden<R: RetryStrategy>(
+    session: &mut Session<R>,
+    path: &str,
+    fullnames: &[Fullname],
+) -> Result<(), RawrCoreError> {
+    if fullnames.iter().any(|fullname| fullname.kind() != ThingKind::Link) {
+        return Err(RawrCoreError::InvalidInvocation("only submissions can be hidden"));
+    }
+
+    for batch in fullnames.chunks(HIDE_BATCH_SIZE) {
+        post_form(session, path, &[("id", join_fullnames(batch))]).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn hide<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullnames: &[Fullname],
+) -> Result<(), RawrCoreError> {
+    set_hidden(session, constants::HIDE_PATH.as_str(), fullnames).await
+}
+
+pub async fn unhide<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullnames: &[Fullname],
+) -> Result<(), RawrCoreError> {
+    set_hidden(session, constants::UNHIDE_PATH.as_str(), fullnames).await
+}
+
+pub async fn report<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    reason: &ReportReason,
+) -> Result<(), RawrCoreError> {
+    if !matches!(
+        fullname.kind(),
+        ThingKind::Link | ThingKind::Comment | ThingKind::Message
+    ) {
+        return Err(RawrCoreError::InvalidInvocation(
+            "only submissions, comments and messages can be reported",
+        ));
+    }
+
+    let length = reason.text().chars().count();
+    if length == 0 || length > MAX_REPORT_REASON_LENGTH {
+        return Err(RawrCoreError::InvalidInvocation(
+            "report reasons must be between 1 and 100 characters",
+        ));
+    }
+
+    let mut data = vec![
+        ("api_type", "json".to_string()),
+        ("thing_id", fullname.to_string()),
+    ];
+    data.extend(reason.form_data());
+
+    let value = post_form(session, constants::REPORT_PATH.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, MockServer};
+    use serde_json::json;
+
+    fn server() -> MockServer {
+        MockServer::start(|_| (200, json!({"json": {"errors": []}})))
+    }
+
+    fn fullname(fullname: &str) -> Fullname {
+        fullname.parse().ok().unwrap()
+    }
+
+    fn links(count: u64) -> Vec<Fullname> {
+        (1..=count)
+            .map(|n| Fullname::from_id_number(ThingKind::Link, n))
+            .collect()
+    }
+
+    #[test]
+    fn votes_are_sent_as_directions() {
+        let server = server();
+        let mut session = server.session();
+        let runtime = test_util::runtime();
+        let comment = fullname("t1_abc");
+
+        for direction in [VoteDirection::Up, VoteDirection::Clear, VoteDirection::Down] {
+            assert!(runtime
+                .block_on(vote(&mut session, &comment, direction))
+                .is_ok());
+        }
+
+        let directions: Vec<Option<String>> = server
+            .requests()
+            .iter()
+            .map(|request| request.form("dir"))
+            .collect();
+        assert_eq!(
+            directions,
+            [
+                Some("1".to_string()),
+                Some("0".to_string()),
+                Some("-1".to_string())
+            ]
+        );
+        assert_eq!(server.requests()[0].form("id").as_deref(), Some("t1_abc"));
+    }
+
+    #[test]
+    fn hiding_is_batched_by_50() {
+        let server = server();
+        let mut session = server.session();
+        let fullnames = links(120);
+
+        assert!(test_util::runtime()
+            .block_on(hide(&mut session, &fullnames))
+            .is_ok());
+
+        let requests = server.requests();
+        let batches: Vec<usize> = requests
+            .iter()
+            .map(|request| request.form("id").unwrap_or_default().split(',').count())
+            .collect();
+        assert_eq!(batches, [50, 50, 20]);
+        assert_eq!(requests[0].path, constants::HIDE_PATH.as_str());
+        assert_eq!(
+            requests[0].form("id"),
+            Some(join_fullnames(&fullnames[..50]))
+        );
+    }
+
+    #[test]
+    fn only_submissions_can_be_hidden() {
+        let server = server();
+        let mut session = server.session();
+        let fullnames = vec![fullname("t3_abc"), fullname("t1_abc")];
+
+        assert!(matches!(
+            test_util::runtime().block_on(unhide(&mut session, &fullnames)),
+            Err(RawrCoreError::InvalidInvocation(_))
+        ));
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn report_reasons_must_be_between_1_and_100_characters() {
+        let server = server();
+        let mut session = server.session();
+        let runtime = test_util::runtime();
+        let link = fullname("t3_abc");
+
+        for reason in [String::new(), "a".repeat(101)] {
+            assert!(matches!(
+                runtime.block_on(report(&mut session, &link, &ReportReason::Other(reason))),
+                Err(RawrCoreError::InvalidInvocation(_))
+            ));
+        }
+        assert!(server.requests().is_empty());
+
+        let reason = ReportReason::Other("a".repeat(100));
+        assert!(runtime
+            .block_on(report(&mut session, &link, &reason))
+            .is_ok());
+
+        let request = server.requests().remove(0);
+        assert_eq!(request.form("thing_id").as_deref(), Some("t3_abc"));
+        assert_eq!(request.form("reason").as_deref(), Some("other"));
+        assert_eq!(
+            request.form("other_reason").map(|reason| reason.len()),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn rule_reports_name_the_rule() {
+        let data = ReportReason::Rule("No spam".to_string()).form_data();
+
+        assert_eq!(data[0], ("reason", "No spam".to_string()));
+        assert_eq!(data[1], ("rule_reason", "No spam".to_string()));
+    }
+}
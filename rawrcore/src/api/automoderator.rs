@@ -0,0 +1,187 @@
+use serde::Deserialize;
+use serde_yaml::Value as YamlValue;
+
+use super::wiki;
+use crate::{
+    errors::{AutoModeratorConfigErrorData, RawrCoreError},
+    sessions::{RetryStrategy, Session},
+};
+
+const CONFIG_PAGE: &str = "config/automoderator";
+const RULE_TYPES: [&str; 8] = [
+    "any",
+    "submission",
+    "comment",
+    "text submission",
+    "link submission",
+    "crosspost submission",
+    "poll submission",
+    "gallery submission",
+];
+const RULE_ACTIONS: [&str; 5] = ["approve", "remove", "spam", "filter", "report"];
+const BOOLEAN_KEYS: [&str; 3] = ["moderators_exempt", "is_edited", "ignore_blockquotes"];
+// AutoModerator reads its config as YAML 1.1, where these are booleans too
+const YAML_1_1_BOOLEANS: [&str; 20] = [
+    "y", "Y", "yes", "Yes", "YES", "n", "N", "no", "No", "NO", "True", "TRUE", "False", "FALSE", "on",
+    "On", "ON", "off", "Off", "OFF",
+];
+
+fn config_error(rule: usize, message: String) -> RawrCoreError {
+    RawrCoreError::InvalidAutoModeratorConfig(AutoModeratorConfigErrorData { rule, message })
+}
+
+fn describe(value: &YamlValue) -> String {
+    match value.as_str() {
+        Some(s) => format!("\"{}\"", s),
+        None => serde_yaml::to_string(value)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+fn is_boolean(value: &YamlValue) -> bool {
+    match value.as_str() {
+        Some(s) => YAML_1_1_BOOLEANS.contains(&s),
+        None => value.is_bool(),
+    }
+}
+
+fn validate_rule(rule: usize, value: &YamlValue) -> Result<(), RawrCoreError> {
+    let mapping = match value {
+        YamlValue::Mapping(mapping) => mapping,
+        _ => {
+            return Err(config_error(
+                rule,
+                "rules must be a mapping of checks and actions".to_string(),
+            ))
+        }
+    };
+
+    for (key, value) in mapping {
+        let key = match key.as_str() {
+            Some(key) => key,
+            None => return Err(config_error(rule, "rule keys must be strings".to_string())),
+        };
+
+        match key {
+            "type" => match value.as_str() {
+                Some(rule_type) if RULE_TYPES.contains(&rule_type) => (),
+                _ => return Err(config_error(rule, format!("unknown type {}", describe(value)))),
+            },
+            "action" => match value.as_str() {
+                Some(action) if RULE_ACTIONS.contains(&action) => (),
+                _ => return Err(config_error(rule, format!("unknown action {}", describe(value)))),
+            },
+            "priority" if !value.is_i64() => {
+                return Err(config_error(rule, "priority must be an integer".to_string()))
+            }
+            _ if BOOLEAN_KEYS.contains(&key) && !is_boolean(value) => {
+                return Err(config_error(rule, format!("{} must be true or false", key)))
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+// checks that every `---` separated rule is well formed YAML and that the
+// values AutoModerator is strict about are valid. returns the rule count
+pub fn validate(config: &str) -> Result<usize, RawrCoreError> {
+    let mut rules = 0;
+
+    for (rule, document) in serde_yaml::Deserializer::from_str(config).enumerate() {
+        let value = match YamlValue::deserialize(document) {
+            Ok(value) => value,
+            Err(e) => return Err(config_error(rule, e.to_string())),
+        };
+
+        // blank documents, e.g. from a leading or trailing separator
+        if value.is_null() {
+            continue;
+        }
+
+        validate_rule(rule, &value)?;
+        rules += 1;
+    }
+
+    Ok(rules)
+}
+
+pub async fn config<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+) -> Result<String, RawrCoreError> {
+    Ok(wiki::page(session, subreddit, CONFIG_PAGE, None).await?.content_md)
+}
+
+// the config is validated locally first; reddit rejects configs it cannot
+// parse as well, but with far less detail. `previous` guards against
+// overwriting a concurrent edit, as with `wiki::edit`
+pub async fn save<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    config: &str,
+    previous: Option<&str>,
+    reason: Option<&str>,
+) -> Result<(), RawrCoreError> {
+    validate(config)?;
+
+    wiki::edit(session, subreddit, CONFIG_PAGE, config, previous, reason).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_rule(config: &str) -> Option<usize> {
+        match validate(config) {
+            Err(RawrCoreError::InvalidAutoModeratorConfig(data)) => Some(data.rule),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn counts_rules_and_skips_blank_documents() {
+        let config = "---\ntype: comment\naction: remove\n---\ntype: submission\naction: filter\n---\n";
+
+        assert!(matches!(validate(config), Ok(2)));
+    }
+
+    #[test]
+    fn accepts_yaml_1_1_booleans() {
+        for spelling in [
+            "true", "false", "True", "FALSE", "yes", "no", "Yes", "NO", "on", "Off", "y", "N",
+        ] {
+            let config = format!("type: comment\nmoderators_exempt: {}\naction: remove", spelling);
+
+            assert!(matches!(validate(config.as_str()), Ok(1)), "{} was rejected", spelling);
+        }
+    }
+
+    #[test]
+    fn rejects_non_boolean_values_for_boolean_keys() {
+        assert_eq!(error_rule("type: comment\nis_edited: maybe"), Some(0));
+    }
+
+    #[test]
+    fn rejects_unknown_types_and_actions() {
+        assert_eq!(error_rule("type: comment\n---\ntype: video"), Some(1));
+        assert_eq!(error_rule("action: delete"), Some(0));
+    }
+
+    #[test]
+    fn rejects_non_integer_priority() {
+        assert_eq!(error_rule("priority: high\naction: remove"), Some(0));
+    }
+
+    #[test]
+    fn rejects_malformed_yaml() {
+        assert_eq!(error_rule("type: comment\n---\ntype: [comment"), Some(1));
+    }
+
+    #[test]
+    fn rejects_rules_that_are_not_mappings() {
+        assert_eq!(error_rule("- type: comment"), Some(0));
+    }
+}
@@ -0,0 +1,272 @@
+use serde_json::Value;
+
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    models::{
+        fullname::{Fullname, ThingKind},
+        things::{Comment, Message, Thing},
+    },
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+#[derive(Clone)]
+pub enum CommentBody {
+    Markdown(String),
+    // reddit's richtext document format, as produced by the new editor
+    RichText(Value),
+}
+impl CommentBody {
+    fn form_field(&self) -> (&'static str, String) {
+        match self {
+            CommentBody::Markdown(text) => ("text", text.clone()),
+            CommentBody::RichText(document) => ("richtext_json", document.to_string()),
+        }
+    }
+}
+
+async fn post_user_text<R: RetryStrategy>(
+    session: &mut Session<R>,
+    path: &str,
+    thing_id: &Fullname,
+    body: &CommentBody,
+) -> Result<Thing, RawrCoreError> {
+    let (field, text) = body.form_field();
+    let data = [
+        ("api_type", "json".to_string()),
+        ("thing_id", thing_id.to_string()),
+        (field, text),
+    ];
+
+    let value = session
+        .request::<[(&str, String); 3], (), (), ()>(
+            RequestMethod::Post,
+            path,
+            Some(&data),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    let mut data = util::json_api_data(value)?;
+
+    util::from_json_value::<Thing>(data["things"][0].take())
+}
+
+fn into_comment(thing: Thing) -> Result<Comment, RawrCoreError> {
+    match thing {
+        Thing::Comment(comment) => Ok(*comment),
+        _ => Err(RawrCoreError::InvalidInvocation(
+            "reddit did not respond with a comment",
+        )),
+    }
+}
+
+// replies to a submission or comment
+pub async fn reply<R: RetryStrategy>(
+    session: &mut Session<R>,
+    parent: &Fullname,
+    body: &CommentBody,
+) -> Result<Comment, RawrCoreError> {
+    if !matches!(parent.kind(), ThingKind::Link | ThingKind::Comment) {
+        return Err(RawrCoreError::InvalidInvocation(
+            "comments can only reply to submissions and comments",
+        ));
+    }
+
+    let thing = post_user_text(session, constants::COMMENT_PATH.as_str(), parent, body).await?;
+
+    into_comment(thing)
+}
+
+pub async fn reply_to_message<R: RetryStrategy>(
+    session: &mut Session<R>,
+    parent: &Fullname,
+    body: &CommentBody,
+) -> Result<Message, RawrCoreError> {
+    if parent.kind() != ThingKind::Message {
+        return Err(RawrCoreError::InvalidInvocation("parent must be a message"));
+    }
+
+    match post_user_text(session, constants::COMMENT_PATH.as_str(), parent, body).await? {
+        Thing::Message(message) => Ok(*message),
+        _ => Err(RawrCoreError::InvalidInvocation(
+            "reddit did not respond with a message",
+        )),
+    }
+}
+
+pub async fn edit<R: RetryStrategy>(
+    session: &mut Session<R>,
+    comment: &Fullname,
+    body: &CommentBody,
+) -> Result<Comment, RawrCoreError> {
+    if comment.kind() != ThingKind::Comment {
+        return Err(RawrCoreError::InvalidInvocation("only comments can be edited"));
+    }
+
+    let thing = post_user_text(session, constants::EDIT_USER_TEXT_PATH.as_str(), comment, body).await?;
+
+    into_comment(thing)
+}
+
+pub async fn delete<R: RetryStrategy>(
+    session: &mut Session<R>,
+    comment: &Fullname,
+) -> Result<(), RawrCoreError> {
+    if comment.kind() != ThingKind::Comment {
+        return Err(RawrCoreError::InvalidInvocation("only comments can be deleted"));
+    }
+
+    let data = [("id", comment.to_string())];
+
+    session
+        .request::<[(&str, String); 1], (), (), ()>(
+            RequestMethod::Post,
+            constants::DELETE_PATH.as_str(),
+            Some(&data),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        errors::RedditErrorType,
+        test_util::{self, MockServer},
+    };
+    use serde_json::json;
+
+    fn comment(id: &str) -> Value {
+        json!({"kind": "t1", "data": {
+            "id": id,
+            "name": format!("t1_{}", id),
+            "author": "spez",
+            "body": "hello",
+            "link_id": "t3_post",
+            "parent_id": "t3_post",
+            "subreddit": "rust",
+            "subreddit_id": "t5_2qh1i",
+            "score": 1,
+            "created_utc": 1.0,
+        }})
+    }
+
+    fn created(id: &str) -> Value {
+        json!({"json": {"errors": [], "data": {"things": [comment(id)]}}})
+    }
+
+    fn fullname(fullname: &str) -> Fullname {
+        fullname.parse().ok().unwrap()
+    }
+
+    #[test]
+    fn reply_posts_the_parent_and_text() {
+        let server = MockServer::start(|_| (200, created("abc")));
+        let mut session = server.session();
+        let body = CommentBody::Markdown("hello".to_string());
+
+        let comment = test_util::runtime()
+            .block_on(reply(&mut session, &fullname("t3_post"), &body))
+            .ok()
+            .unwrap();
+        assert_eq!(comment.id, "abc");
+
+        let request = server.requests().remove(0);
+        assert_eq!(request.path, constants::COMMENT_PATH.as_str());
+        assert_eq!(request.form("api_type").as_deref(), Some("json"));
+        assert_eq!(request.form("thing_id").as_deref(), Some("t3_post"));
+        assert_eq!(request.form("text").as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn richtext_bodies_are_sent_as_json() {
+        let server = MockServer::start(|_| (200, created("abc")));
+        let mut session = server.session();
+        let body = CommentBody::RichText(json!({"document": []}));
+
+        let result = test_util::runtime().block_on(edit(&mut session, &fullname("t1_abc"), &body));
+        assert!(result.is_ok());
+
+        let request = server.requests().remove(0);
+        assert_eq!(request.path, constants::EDIT_USER_TEXT_PATH.as_str());
+        assert_eq!(request.form("richtext_json").as_deref(), Some(r#"{"document":[]}"#));
+        assert_eq!(request.form("text"), None);
+    }
+
+    #[test]
+    fn api_errors_keep_their_fields() {
+        let server = MockServer::start(|_| {
+            (
+                200,
+                json!({"json": {"errors": [
+                    ["RATELIMIT", "you are doing that too much. try again in 5 minutes.", "ratelimit"],
+                ]}}),
+            )
+        });
+        let mut session = server.session();
+        let body = CommentBody::Markdown("hello".to_string());
+
+        match test_util::runtime().block_on(reply(&mut session, &fullname("t1_abc"), &body)) {
+            Err(RawrCoreError::RedditAPIError(data)) => {
+                assert_eq!(data.errors.len(), 1);
+                assert_eq!(data.errors[0].error_type, RedditErrorType::RateLimit);
+                assert!(data.errors[0].message.starts_with("you are doing that too much"));
+                assert_eq!(data.errors[0].field.as_deref(), Some("ratelimit"));
+                assert!(data.contains(&RedditErrorType::RateLimit));
+            }
+            _ => panic!("expected RedditAPIError"),
+        }
+    }
+
+    #[test]
+    fn delete_posts_the_comment_id() {
+        let server = MockServer::start(|_| (200, json!({})));
+        let mut session = server.session();
+
+        let result = test_util::runtime().block_on(delete(&mut session, &fullname("t1_abc")));
+        assert!(result.is_ok());
+
+        let request = server.requests().remove(0);
+        assert_eq!(request.path, constants::DELETE_PATH.as_str());
+        assert_eq!(request.form("id").as_deref(), Some("t1_abc"));
+    }
+
+    #[test]
+    fn wrong_kinds_are_rejected_before_sending() {
+        let server = MockServer::start(|_| (200, created("abc")));
+        let mut session = server.session();
+        let body = CommentBody::Markdown("hello".to_string());
+        let runtime = test_util::runtime();
+
+        let subreddit = fullname("t5_2qh1i");
+        let link = fullname("t3_post");
+
+        assert!(matches!(
+            runtime.block_on(reply(&mut session, &subreddit, &body)),
+            Err(RawrCoreError::InvalidInvocation(_))
+        ));
+        assert!(matches!(
+            runtime.block_on(reply_to_message(&mut session, &link, &body)),
+            Err(RawrCoreError::InvalidInvocation(_))
+        ));
+        assert!(matches!(
+            runtime.block_on(edit(&mut session, &link, &body)),
+            Err(RawrCoreError::InvalidInvocation(_))
+        ));
+        assert!(matches!(
+            runtime.block_on(delete(&mut session, &link)),
+            Err(RawrCoreError::InvalidInvocation(_))
+        ));
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,494 @@
+use std::collections::HashMap;
+
+use super::post_form;
+use crate::{
+    errors::RawrCoreError,
+    models::{
+        flair::{
+            FlairAllowableContent, FlairAssignmentResult, FlairCsvRow, FlairListResponse, FlairRichText,
+            FlairTemplate, FlairTextColor, UserFlair,
+        },
+        fullname::{Fullname, ThingKind},
+    },
+    pagination::ListingPaginator,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const MAX_FLAIR_TEXT_LENGTH: usize = 64;
+const MAX_EMOJIS: u8 = 10;
+const FLAIR_CSV_BATCH_SIZE: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlairKind {
+    Link,
+    User,
+}
+impl FlairKind {
+    fn flair_type(&self) -> &'static str {
+        match self {
+            FlairKind::Link => "LINK_FLAIR",
+            FlairKind::User => "USER_FLAIR",
+        }
+    }
+
+    fn templates_path(&self, subreddit: &str) -> String {
+        match self {
+            FlairKind::Link => format!("/r/{}/api/link_flair_v2", subreddit),
+            FlairKind::User => format!("/r/{}/api/user_flair_v2", subreddit),
+        }
+    }
+}
+
+// who `select_flair` applies to
+#[derive(Clone, Debug)]
+pub enum FlairTarget {
+    Link(Fullname),
+    User(String),
+}
+
+#[derive(Clone)]
+pub struct FlairTemplateDraft {
+    text: String,
+    richtext: Option<Vec<FlairRichText>>,
+    css_class: Option<String>,
+    text_color: FlairTextColor,
+    background_color: Option<String>,
+    text_editable: bool,
+    mod_only: bool,
+    allowable_content: FlairAllowableContent,
+    max_emojis: u8,
+}
+
+impl FlairTemplateDraft {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            richtext: None,
+            css_class: None,
+            text_color: FlairTextColor::Dark,
+            background_color: None,
+            text_editable: false,
+            mod_only: false,
+            allowable_content: FlairAllowableContent::All,
+            max_emojis: MAX_EMOJIS,
+        }
+    }
+
+    // the parts are sent as they are, with their flattened text alongside
+    // for clients that only read plain flair text
+    pub fn richtext(parts: &[FlairRichText]) -> Self {
+        let mut draft = Self::new(FlairRichText::to_text(parts).as_str());
+        draft.richtext = Some(parts.to_vec());
+        draft
+    }
+
+    pub fn css_class(mut self, css_class: &str) -> Self {
+        self.css_class = Some(css_class.to_string());
+        self
+    }
+
+    // `background_color` is a hex color such as "#ff4500"
+    pub fn colors(mut self, text_color: FlairTextColor, background_color: &str) -> Self {
+        self.text_color = text_color;
+        self.background_color = Some(background_color.to_string());
+        self
+    }
+
+    pub fn text_editable(mut self, text_editable: bool) -> Self {
+        self.text_editable = text_editable;
+        self
+    }
+
+    pub fn mod_only(mut self, mod_only: bool) -> Self {
+        self.mod_only = mod_only;
+        self
+    }
+
+    pub fn allowable_content(mut self, allowable_content: FlairAllowableContent) -> Self {
+        self.allowable_content = allowable_content;
+        self
+    }
+
+    pub fn max_emojis(mut self, max_emojis: u8) -> Self {
+        self.max_emojis = max_emojis;
+        self
+    }
+
+    fn validate(&self) -> Result<(), RawrCoreError> {
+        if self.text.chars().count() > MAX_FLAIR_TEXT_LENGTH {
+            return Err(RawrCoreError::InvalidInvocation(
+                "flair text must be at most 64 characters",
+            ));
+        }
+
+        if self.max_emojis == 0 || self.max_emojis > MAX_EMOJIS {
+            return Err(RawrCoreError::InvalidInvocation(
+                "flair templates must allow between 1 and 10 emojis",
+            ));
+        }
+
+        if let Some(background_color) = &self.background_color {
+            let is_hex_color = background_color.len() == 7
+                && background_color.starts_with('#')
+                && background_color[1..].chars().all(|c| c.is_ascii_hexdigit());
+
+            if !is_hex_color {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "flair background colors must be hex colors like #ff4500",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn form_data(&self, kind: FlairKind) -> Result<Vec<(&'static str, String)>, RawrCoreError> {
+        let mut data = vec![
+            ("flair_type", kind.flair_type().to_string()),
+            ("text", self.text.clone()),
+            ("css_class", self.css_class.clone().unwrap_or_default()),
+            ("text_color", self.text_color.as_str().to_string()),
+            (
+                "background_color",
+                self.background_color.clone().unwrap_or_else(|| "transparent".to_string()),
+            ),
+            ("text_editable", self.text_editable.to_string()),
+            ("mod_only", self.mod_only.to_string()),
+            ("allowable_content", self.allowable_content.as_str().to_string()),
+            ("max_emojis", self.max_emojis.to_string()),
+        ];
+
+        if let Some(richtext) = &self.richtext {
+            match serde_json::to_string(richtext) {
+                Ok(richtext) => data.push(("richtext", richtext)),
+                Err(_) => {
+                    return Err(RawrCoreError::InvalidInvocation(
+                        "flair richtext could not be serialized",
+                    ))
+                }
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+// one row of a bulk assignment; empty text and css class clear the flair
+#[derive(Clone)]
+pub struct FlairAssignment {
+    pub user: String,
+    pub text: String,
+    pub css_class: String,
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn assignment_results(batch: &[FlairAssignment], rows: Vec<FlairCsvRow>) -> Vec<FlairAssignmentResult> {
+    let mut rows = rows.into_iter();
+
+    batch
+        .iter()
+        .map(|assignment| match rows.next() {
+            Some(row) => FlairAssignmentResult {
+                user: assignment.user.clone(),
+                ok: row.ok,
+                status: row.status,
+                errors: row.errors,
+                warnings: row.warnings,
+            },
+            None => FlairAssignmentResult {
+                user: assignment.user.clone(),
+                ok: false,
+                status: "no result was returned for this row".to_string(),
+                errors: HashMap::new(),
+                warnings: HashMap::new(),
+            },
+        })
+        .collect()
+}
+
+pub async fn templates<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    kind: FlairKind,
+) -> Result<Vec<FlairTemplate>, RawrCoreError> {
+    let value = session
+        .request::<(), (), (), ()>(
+            RequestMethod::Get,
+            kind.templates_path(subreddit).as_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    util::from_json_value::<Vec<FlairTemplate>>(value)
+}
+
+async fn save_template<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    kind: FlairKind,
+    id: Option<&str>,
+    draft: &FlairTemplateDraft,
+) -> Result<FlairTemplate, RawrCoreError> {
+    draft.validate()?;
+
+    let path = format!("/r/{}/api/flairtemplate_v2", subreddit);
+    let mut data = draft.form_data(kind)?;
+
+    if let Some(id) = id {
+        data.push(("flair_template_id", id.to_string()));
+    }
+
+    let value = post_form(session, path.as_str(), &data).await?;
+
+    util::from_json_value::<FlairTemplate>(value)
+}
+
+pub async fn add_template<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    kind: FlairKind,
+    draft: &FlairTemplateDraft,
+) -> Result<FlairTemplate, RawrCoreError> {
+    save_template(session, subreddit, kind, None, draft).await
+}
+
+pub async fn update_template<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    kind: FlairKind,
+    id: &str,
+    draft: &FlairTemplateDraft,
+) -> Result<FlairTemplate, RawrCoreError> {
+    save_template(session, subreddit, kind, Some(id), draft).await
+}
+
+pub async fn delete_template<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    id: &str,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/r/{}/api/deleteflairtemplate", subreddit);
+
+    post_form(session, path.as_str(), &[("flair_template_id", id.to_string())]).await?;
+
+    Ok(())
+}
+
+pub async fn clear_templates<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    kind: FlairKind,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/r/{}/api/clearflairtemplates", subreddit);
+
+    post_form(session, path.as_str(), &[("flair_type", kind.flair_type().to_string())]).await?;
+
+    Ok(())
+}
+
+// applies a template, optionally overriding its text when the template is
+// text editable. without a template, `text` is set as plain flair
+pub async fn select_flair<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    target: &FlairTarget,
+    template_id: Option<&str>,
+    text: Option<&str>,
+) -> Result<(), RawrCoreError> {
+    if let Some(text) = text {
+        if text.chars().count() > MAX_FLAIR_TEXT_LENGTH {
+            return Err(RawrCoreError::InvalidInvocation(
+                "flair text must be at most 64 characters",
+            ));
+        }
+    }
+
+    let path = format!("/r/{}/api/selectflair", subreddit);
+    let mut data = vec![("api_type", "json".to_string())];
+
+    match target {
+        FlairTarget::Link(fullname) if fullname.kind() == ThingKind::Link => {
+            data.push(("link", fullname.to_string()))
+        }
+        FlairTarget::Link(_) => {
+            return Err(RawrCoreError::InvalidInvocation("only submissions can have link flair"))
+        }
+        FlairTarget::User(user) => data.push(("name", user.clone())),
+    }
+
+    if let Some(template_id) = template_id {
+        data.push(("flair_template_id", template_id.to_string()));
+    }
+
+    if let Some(text) = text {
+        data.push(("text", text.to_string()));
+    }
+
+    let value = post_form(session, path.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+// sets user flair in batches of 100 rows. a failed row does not stop the
+// rest, so check every result. rows reddit gives no result for are reported
+// as failed rather than left out
+pub async fn assign_user_flair<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    assignments: &[FlairAssignment],
+) -> Result<Vec<FlairAssignmentResult>, RawrCoreError> {
+    let path = format!("/r/{}/api/flaircsv", subreddit);
+    let mut results = Vec::with_capacity(assignments.len());
+
+    for batch in assignments.chunks(FLAIR_CSV_BATCH_SIZE) {
+        let csv = batch
+            .iter()
+            .map(|row| {
+                format!(
+                    "{},{},{}",
+                    csv_field(row.user.as_str()),
+                    csv_field(row.text.as_str()),
+                    csv_field(row.css_class.as_str())
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let value = post_form(session, path.as_str(), &[("flair_csv", csv)]).await?;
+        let rows = util::from_json_value::<Vec<FlairCsvRow>>(value)?;
+
+        results.extend(assignment_results(batch, rows));
+    }
+
+    Ok(results)
+}
+
+pub async fn delete_flair<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/r/{}/api/deleteflair", subreddit);
+    let data = [("api_type", "json".to_string()), ("name", user.to_string())];
+
+    let value = post_form(session, path.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+// the flair of a single user, if they have any
+pub async fn user_flair<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+) -> Result<Option<UserFlair>, RawrCoreError> {
+    let path = format!("/r/{}/api/flairlist", subreddit);
+    let params = [("name", user.to_string())];
+    let value = session
+        .request::<(), (), (), [(&str, String); 1]>(
+            RequestMethod::Get,
+            path.as_str(),
+            None,
+            None,
+            None,
+            Some(&params),
+            None,
+        )
+        .await?;
+
+    Ok(util::from_json_value::<FlairListResponse>(value)?
+        .users
+        .into_iter()
+        .find(|flair| flair.user.eq_ignore_ascii_case(user)))
+}
+
+pub fn flair_list<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    subreddit: &str,
+    limit: Option<usize>,
+    after: Option<String>,
+) -> ListingPaginator<'s, R, UserFlair, FlairListResponse> {
+    let path = format!("/r/{}/api/flairlist", subreddit);
+
+    ListingPaginator::new(session, path.as_str(), None, limit, after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignment(user: &str) -> FlairAssignment {
+        FlairAssignment {
+            user: user.to_string(),
+            text: String::new(),
+            css_class: String::new(),
+        }
+    }
+
+    #[test]
+    fn csv_field_leaves_plain_fields_alone() {
+        assert_eq!(csv_field("spez"), "spez");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn csv_field_quotes_special_characters() {
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn missing_csv_rows_are_reported_as_failed() {
+        let batch = [assignment("a"), assignment("b")];
+        let rows = vec![FlairCsvRow {
+            ok: true,
+            status: "added flair for user a".to_string(),
+            errors: HashMap::new(),
+            warnings: HashMap::new(),
+        }];
+
+        let results = assignment_results(&batch, rows);
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].ok);
+        assert_eq!(results[1].user, "b");
+        assert!(!results[1].ok);
+    }
+
+    #[test]
+    fn richtext_drafts_send_their_parts() {
+        let parts = [
+            FlairRichText::Emoji {
+                name: ":snoo:".to_string(),
+                url: None,
+            },
+            FlairRichText::Text {
+                text: " mod".to_string(),
+            },
+        ];
+
+        let data = FlairTemplateDraft::richtext(&parts).form_data(FlairKind::User).ok().unwrap();
+        let field = |key: &str| data.iter().find(|(k, _)| *k == key).map(|(_, v)| v.clone());
+
+        assert_eq!(field("text").as_deref(), Some(":snoo: mod"));
+        assert_eq!(
+            field("richtext").as_deref(),
+            Some(r#"[{"e":"emoji","a":":snoo:"},{"e":"text","t":" mod"}]"#)
+        );
+    }
+}
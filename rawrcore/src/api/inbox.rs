@@ -0,0 +1,162 @@
+use super::{join_fullnames, post_form};
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    models::{
+        fullname::{Fullname, ThingKind},
+        things::InboxItem,
+    },
+    pagination::ListingPaginator,
+    sessions::{RetryStrategy, Session},
+    stream::ItemStream,
+    util,
+};
+
+const MARK_BATCH_SIZE: usize = 25;
+const MAX_SUBJECT_LENGTH: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InboxFolder {
+    Inbox,
+    Unread,
+    Sent,
+    Messages,
+    Mentions,
+    CommentReplies,
+    SelfReplies,
+}
+impl InboxFolder {
+    pub fn path(&self) -> &'static str {
+        match self {
+            InboxFolder::Inbox => "/message/inbox",
+            InboxFolder::Unread => "/message/unread",
+            InboxFolder::Sent => "/message/sent",
+            InboxFolder::Messages => "/message/messages",
+            InboxFolder::Mentions => "/message/mentions",
+            InboxFolder::CommentReplies => "/message/comments",
+            InboxFolder::SelfReplies => "/message/selfreply",
+        }
+    }
+}
+
+// listing unread items does not mark them as read
+pub fn listing<R: RetryStrategy>(
+    session: &mut Session<R>,
+    folder: InboxFolder,
+    limit: Option<usize>,
+) -> ListingPaginator<'_, R, InboxItem> {
+    let params = match folder {
+        InboxFolder::Unread => Some(vec![("mark".to_string(), "false".to_string())]),
+        _ => None,
+    };
+
+    ListingPaginator::new(session, folder.path(), params, limit, None)
+}
+
+pub fn stream<R: RetryStrategy>(
+    session: &mut Session<R>,
+    skip_existing: bool,
+    pause_after: Option<usize>,
+) -> ItemStream<'_, R, InboxItem> {
+    ItemStream::new(
+        session,
+        InboxFolder::Inbox.path(),
+        None,
+        skip_existing,
+        pause_after,
+    )
+}
+
+// `to` is a username, or "/r/<subreddit>" to message its moderators.
+// `from_subreddit` sends the message as a subreddit you moderate
+pub async fn compose<R: RetryStrategy>(
+    session: &mut Session<R>,
+    to: &str,
+    subject: &str,
+    text: &str,
+    from_subreddit: Option<&str>,
+) -> Result<(), RawrCoreError> {
+    if to.is_empty() {
+        return Err(RawrCoreError::InvalidInvocation("recipient must not be empty"));
+    }
+
+    let subject_length = subject.chars().count();
+    if subject_length == 0 || subject_length > MAX_SUBJECT_LENGTH {
+        return Err(RawrCoreError::InvalidInvocation(
+            "subject must be between 1 and 100 characters",
+        ));
+    }
+
+    let mut data = vec![
+        ("api_type", "json".to_string()),
+        ("to", to.to_string()),
+        ("subject", subject.to_string()),
+        ("text", text.to_string()),
+    ];
+
+    if let Some(from_subreddit) = from_subreddit {
+        data.push(("from_sr", from_subreddit.to_string()));
+    }
+
+    let value = post_form(session, constants::COMPOSE_PATH.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+async fn mark<R: RetryStrategy>(
+    session: &mut Session<R>,
+    path: &str,
+    fullnames: &[Fullname],
+) -> Result<(), RawrCoreError> {
+    if fullnames
+        .iter()
+        .any(|fullname| !matches!(fullname.kind(), ThingKind::Comment | ThingKind::Message))
+    {
+        return Err(RawrCoreError::InvalidInvocation(
+            "only messages and comment replies can be marked",
+        ));
+    }
+
+    for batch in fullnames.chunks(MARK_BATCH_SIZE) {
+        post_form(session, path, &[("id", join_fullnames(batch))]).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn read_message<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullnames: &[Fullname],
+) -> Result<(), RawrCoreError> {
+    mark(session, constants::READ_MESSAGE_PATH.as_str(), fullnames).await
+}
+
+pub async fn unread_message<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullnames: &[Fullname],
+) -> Result<(), RawrCoreError> {
+    mark(session, constants::UNREAD_MESSAGE_PATH.as_str(), fullnames).await
+}
+
+pub async fn read_all<R: RetryStrategy>(session: &mut Session<R>) -> Result<(), RawrCoreError> {
+    post_form(session, constants::READ_ALL_MESSAGES_PATH.as_str(), &[]).await?;
+
+    Ok(())
+}
+
+// blocks the author of the given message or comment reply
+pub async fn block_author<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+) -> Result<(), RawrCoreError> {
+    if !matches!(fullname.kind(), ThingKind::Comment | ThingKind::Message) {
+        return Err(RawrCoreError::InvalidInvocation(
+            "authors can only be blocked through their messages and comments",
+        ));
+    }
+
+    post_form(session, constants::BLOCK_PATH.as_str(), &[("id", fullname.to_string())]).await?;
+
+    Ok(())
+}
@@ -0,0 +1,355 @@
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+use crate::{
+    constants,
+    errors::{MediaProcessingData, RawrCoreError},
+    models::fullname::{Fullname, ThingKind},
+    sessions::{RequestMethod, RetryStrategy, Session},
+    upload::{MediaKind, UploadFile},
+    util,
+};
+
+const PROCESSING_TIMEOUT_SECONDS: f32 = 120.0;
+
+#[derive(Deserialize)]
+struct LeaseField {
+    name: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct LeaseArgs {
+    action: String,
+    fields: Vec<LeaseField>,
+}
+
+#[derive(Deserialize)]
+struct LeaseAsset {
+    asset_id: String,
+    websocket_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UploadLease {
+    args: LeaseArgs,
+    asset: LeaseAsset,
+}
+
+#[derive(Deserialize)]
+struct ProcessingPayload {
+    redirect: Option<String>,
+}
+
+// what the submission websocket sends once processing is done
+#[derive(Deserialize)]
+struct ProcessingMessage {
+    #[serde(rename = "type")]
+    message_type: String,
+    payload: Option<ProcessingPayload>,
+}
+
+#[derive(Clone)]
+pub struct UploadedMedia {
+    pub asset_id: String,
+    pub url: String,
+    pub kind: MediaKind,
+    pub websocket_url: Option<String>,
+}
+
+pub struct MediaUpload {
+    file: UploadFile,
+    upload_url: Option<String>,
+}
+
+impl MediaUpload {
+    pub fn new(file: UploadFile) -> Self {
+        Self {
+            file: file.field_name("file"),
+            upload_url: None,
+        }
+    }
+
+    // sends the file to `upload_url` instead of the leased url, e.g. a local
+    // mock endpoint; the lease itself is still requested from reddit
+    pub fn upload_url(mut self, upload_url: &str) -> Self {
+        self.upload_url = Some(upload_url.to_string());
+        self
+    }
+
+    pub async fn upload<R: RetryStrategy>(
+        &self,
+        session: &mut Session<R>,
+    ) -> Result<UploadedMedia, RawrCoreError> {
+        let kind = self.file.validate_media()?;
+
+        let data = [
+            ("filepath", self.file.file_name()),
+            ("mimetype", self.file.mime_type()),
+        ];
+        let value = session
+            .request::<[(&str, &str); 2], (), (), ()>(
+                RequestMethod::Post,
+                constants::MEDIA_ASSET_PATH.as_str(),
+                Some(&data),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await?;
+        let lease = util::from_json_value::<UploadLease>(value)?;
+
+        // leases are handed out as protocol relative urls
+        let action = match lease.args.action.strip_prefix("//") {
+            Some(host) => format!("https://{}", host),
+            None => lease.args.action,
+        };
+
+        let fields: Vec<(String, String)> = lease
+            .args
+            .fields
+            .into_iter()
+            .map(|field| (field.name, field.value))
+            .collect();
+
+        let key = match fields.iter().find(|(name, _)| name.eq("key")) {
+            Some((_, key)) => key.clone(),
+            None => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "upload lease did not include a key",
+                ))
+            }
+        };
+
+        let upload_url = self.upload_url.as_deref().unwrap_or(action.as_str());
+        session
+            .upload(upload_url, Some(&fields), &self.file, None)
+            .await?;
+
+        Ok(UploadedMedia {
+            asset_id: lease.asset.asset_id,
+            url: format!("{}/{}", action, key),
+            kind,
+            websocket_url: lease.asset.websocket_url,
+        })
+    }
+}
+
+// media posts are created asynchronously once reddit has processed the
+// upload, so /api/submit only hands back a websocket that reports the
+// outcome. waits on it for the fullname of the new post
+pub(crate) async fn wait_for_media_post(submit_data: Value) -> Result<Fullname, RawrCoreError> {
+    let processing = MediaProcessingData {
+        websocket_url: submit_data["websocket_url"]
+            .as_str()
+            .map(|url| url.to_string()),
+        user_submitted_page: submit_data["user_submitted_page"]
+            .as_str()
+            .map(|page| page.to_string()),
+    };
+
+    let websocket_url = match &processing.websocket_url {
+        Some(websocket_url) => websocket_url.clone(),
+        None => return Err(RawrCoreError::MediaProcessingFailed(processing)),
+    };
+
+    let timeout = Duration::from_secs_f32(PROCESSING_TIMEOUT_SECONDS);
+    match tokio::time::timeout(timeout, processing_outcome(websocket_url.as_str())).await {
+        Ok(Some(fullname)) => Ok(fullname),
+        Ok(None) => Err(RawrCoreError::MediaProcessingFailed(processing)),
+        Err(_) => Err(RawrCoreError::MediaProcessingTimeout(processing)),
+    }
+}
+
+// the post reddit created, or None when processing failed or the socket
+// closed before an outcome was sent
+async fn processing_outcome(websocket_url: &str) -> Option<Fullname> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(websocket_url).await.ok()?;
+
+    while let Some(message) = socket.next().await {
+        let text = match message.ok()? {
+            Message::Text(text) => text,
+            _ => continue,
+        };
+
+        let message = match serde_json::from_str::<ProcessingMessage>(text.as_str()) {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+
+        match message.message_type.as_str() {
+            "success" => {
+                return message
+                    .payload
+                    .and_then(|payload| payload.redirect)
+                    .and_then(|redirect| redirect_fullname(redirect.as_str()))
+            }
+            "failed" => return None,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+// successes redirect to the post, e.g. /r/{sub}/comments/{id}/{slug}/
+fn redirect_fullname(redirect: &str) -> Option<Fullname> {
+    let url = Url::parse(redirect).ok()?;
+    let mut segments = url.path_segments()?;
+    segments.find(|segment| segment.eq(&"comments"))?;
+
+    Fullname::new(ThingKind::Link, segments.next()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::submissions::SubmissionBuilder,
+        test_util::{self, MockRequest, MockServer},
+    };
+    use serde_json::json;
+    use std::{net::TcpListener, thread};
+    use tokio_tungstenite::tungstenite;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n0000";
+
+    // answers the lease, upload and submit endpoints like reddit and s3 would
+    fn mock_reddit(websocket_url: String) -> MockServer {
+        MockServer::start(move |request| match request.path.as_str() {
+            "/api/media/asset.json" => (
+                200,
+                json!({
+                    "args": {
+                        "action": "//reddit-uploaded-media.s3-accelerate.amazonaws.com",
+                        "fields": [
+                            {"name": "key", "value": "rte_images/abc"},
+                            {"name": "policy", "value": "signed"},
+                        ],
+                    },
+                    "asset": {"asset_id": "abc", "websocket_url": null},
+                }),
+            ),
+            "/upload" => (201, Value::Null),
+            "/api/submit" => (
+                200,
+                json!({"json": {"errors": [], "data": {
+                    "websocket_url": websocket_url,
+                    "user_submitted_page": "https://www.reddit.com/user/tester/submitted/",
+                }}}),
+            ),
+            _ => (404, Value::Null),
+        })
+    }
+
+    // a submission websocket that sends `message` to whoever connects
+    fn mock_websocket(message: Value) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("ws://{}/rte_images/abc", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut socket = tungstenite::accept(stream.unwrap()).unwrap();
+                socket.send(Message::Text(message.to_string())).unwrap();
+                let _ = socket.close(None);
+                while socket.read().is_ok() {}
+            }
+        });
+
+        address
+    }
+
+    fn submit_image(server: &MockServer) -> Result<Fullname, RawrCoreError> {
+        let mut session = server.session();
+
+        test_util::runtime().block_on(async {
+            let upload = MediaUpload::new(UploadFile::from_bytes("a.png", PNG.to_vec())?)
+                .upload_url(format!("{}/upload", server.url).as_str());
+            let media = upload.upload(&mut session).await?;
+
+            SubmissionBuilder::image("test", "an image", &media)
+                .submit(&mut session)
+                .await
+        })
+    }
+
+    fn request_to(server: &MockServer, path: &str) -> MockRequest {
+        match server
+            .requests()
+            .into_iter()
+            .find(|request| request.path.eq(path))
+        {
+            Some(request) => request,
+            None => panic!("expected a request to {}", path),
+        }
+    }
+
+    #[test]
+    fn uploads_to_the_lease_and_waits_for_the_post() {
+        let server = mock_reddit(mock_websocket(json!({
+            "type": "success",
+            "payload": {"redirect": "https://www.reddit.com/r/test/comments/abc123/an_image/"},
+        })));
+        let fullname = submit_image(&server).ok().unwrap();
+
+        assert_eq!(fullname.to_string(), "t3_abc123");
+
+        let lease = request_to(&server, "/api/media/asset.json");
+        assert_eq!(lease.method, "POST");
+        assert_eq!(lease.param("raw_json"), Some("1"));
+        assert_eq!(lease.form("filepath").as_deref(), Some("a.png"));
+        assert_eq!(lease.form("mimetype").as_deref(), Some("image/png"));
+
+        let upload = request_to(&server, "/upload");
+        assert!(upload.body.contains("rte_images/abc"));
+        assert!(upload.body.contains("signed"));
+        assert!(upload.body.contains("\x1a\n0000"));
+
+        let submit = request_to(&server, "/api/submit");
+        assert_eq!(submit.form("kind").as_deref(), Some("image"));
+        assert_eq!(
+            submit.form("url").as_deref(),
+            Some("https://reddit-uploaded-media.s3-accelerate.amazonaws.com/rte_images/abc")
+        );
+    }
+
+    #[test]
+    fn failed_processing_is_reported() {
+        let server = mock_reddit(mock_websocket(json!({"type": "failed", "payload": {}})));
+
+        match submit_image(&server) {
+            Err(RawrCoreError::MediaProcessingFailed(data)) => assert_eq!(
+                data.user_submitted_page.as_deref(),
+                Some("https://www.reddit.com/user/tester/submitted/")
+            ),
+            _ => panic!("expected MediaProcessingFailed"),
+        }
+    }
+
+    #[test]
+    fn submissions_without_a_websocket_fail() {
+        let result = test_util::runtime()
+            .block_on(wait_for_media_post(json!({"user_submitted_page": "page"})));
+
+        assert!(matches!(
+            result,
+            Err(RawrCoreError::MediaProcessingFailed(_))
+        ));
+    }
+
+    #[test]
+    fn redirect_fullname_reads_the_post_id() {
+        assert_eq!(
+            redirect_fullname("https://www.reddit.com/r/test/comments/abc123/title/")
+                .map(|f| f.to_string()),
+            Some("t3_abc123".to_string())
+        );
+        assert!(redirect_fullname("https://www.reddit.com/r/test/").is_none());
+        assert!(redirect_fullname("not a url").is_none());
+    }
+}
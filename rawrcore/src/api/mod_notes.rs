@@ -0,0 +1,242 @@
+use super::post_form;
+use crate::{
+    constants,
+    errors::{BadJSONData, RawrCoreError},
+    models::{
+        fullname::Fullname,
+        mod_note::{CreatedModNote, ModNote, ModNoteLabel, ModNotesResponse, RecentModNotesResponse},
+    },
+    pagination::ListingPaginator,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const MAX_NOTE_LENGTH: usize = 250;
+const RECENT_BATCH_SIZE: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModNoteFilter {
+    All,
+    Note,
+    Approval,
+    Removal,
+    Ban,
+    Mute,
+    Invite,
+    Spam,
+    ContentChange,
+    ModAction,
+}
+impl ModNoteFilter {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ModNoteFilter::All => "ALL",
+            ModNoteFilter::Note => "NOTE",
+            ModNoteFilter::Approval => "APPROVAL",
+            ModNoteFilter::Removal => "REMOVAL",
+            ModNoteFilter::Ban => "BAN",
+            ModNoteFilter::Mute => "MUTE",
+            ModNoteFilter::Invite => "INVITE",
+            ModNoteFilter::Spam => "SPAM",
+            ModNoteFilter::ContentChange => "CONTENT_CHANGE",
+            ModNoteFilter::ModAction => "MOD_ACTION",
+        }
+    }
+}
+
+// `reddit_id` ties the note to a submission or comment by the user
+pub async fn create<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    note: &str,
+    label: Option<ModNoteLabel>,
+    reddit_id: Option<&Fullname>,
+) -> Result<ModNote, RawrCoreError> {
+    let note_length = note.chars().count();
+    if note_length == 0 || note_length > MAX_NOTE_LENGTH {
+        return Err(RawrCoreError::InvalidInvocation(
+            "mod notes must be between 1 and 250 characters",
+        ));
+    }
+
+    let mut data = vec![
+        ("subreddit", subreddit.to_string()),
+        ("user", user.to_string()),
+        ("note", note.to_string()),
+    ];
+
+    if let Some(label) = label {
+        if label == ModNoteLabel::Unknown {
+            return Err(RawrCoreError::InvalidInvocation(
+                "mod notes cannot be created with an unknown label",
+            ));
+        }
+
+        data.push(("label", label.as_str().to_string()));
+    }
+
+    if let Some(reddit_id) = reddit_id {
+        data.push(("reddit_id", reddit_id.to_string()));
+    }
+
+    let value = post_form(session, constants::MOD_NOTES_PATH.as_str(), &data).await?;
+
+    Ok(util::from_json_value::<CreatedModNote>(value)?.created)
+}
+
+// only notes written by moderators can be deleted
+pub async fn delete<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    note_id: &str,
+) -> Result<(), RawrCoreError> {
+    let params = [
+        ("subreddit", subreddit.to_string()),
+        ("user", user.to_string()),
+        ("note_id", note_id.to_string()),
+    ];
+
+    session
+        .request::<(), (), (), [(&str, String); 3]>(
+            RequestMethod::Delete,
+            constants::MOD_NOTES_PATH.as_str(),
+            None,
+            None,
+            None,
+            Some(&params),
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+// the most recent note of each user in each paired subreddit, in the order
+// of `pairs`. pairs are sent 500 at a time
+pub async fn recent<R: RetryStrategy>(
+    session: &mut Session<R>,
+    pairs: &[(&str, &str)],
+) -> Result<Vec<Option<ModNote>>, RawrCoreError> {
+    let mut notes = Vec::with_capacity(pairs.len());
+
+    for batch in pairs.chunks(RECENT_BATCH_SIZE) {
+        let (subreddits, users): (Vec<&str>, Vec<&str>) = batch.iter().copied().unzip();
+        let params = [("subreddits", subreddits.join(",")), ("users", users.join(","))];
+
+        let value = session
+            .request::<(), (), (), [(&str, String); 2]>(
+                RequestMethod::Get,
+                constants::RECENT_MOD_NOTES_PATH.as_str(),
+                None,
+                None,
+                None,
+                Some(&params),
+                None,
+            )
+            .await?;
+
+        let body = value.to_string();
+        let batch_notes = util::from_json_value::<RecentModNotesResponse>(value)?.mod_notes;
+
+        // a short answer would shift every later note onto the wrong pair
+        if batch_notes.len() != batch.len() {
+            return Err(RawrCoreError::BadJSON(BadJSONData {
+                body,
+                original_error: serde::de::Error::invalid_length(
+                    batch_notes.len(),
+                    &"one note per pair",
+                ),
+            }));
+        }
+
+        notes.extend(batch_notes);
+    }
+
+    Ok(notes)
+}
+
+// the notes on `user` in `subreddit`, newest first
+pub fn list<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    filter: ModNoteFilter,
+    limit: Option<usize>,
+    before: Option<String>,
+) -> ListingPaginator<'s, R, ModNote, ModNotesResponse> {
+    let params = vec![
+        ("subreddit".to_string(), subreddit.to_string()),
+        ("user".to_string(), user.to_string()),
+        ("filter".to_string(), filter.as_str().to_string()),
+    ];
+
+    ListingPaginator::new(session, constants::MOD_NOTES_PATH.as_str(), Some(params), limit, before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{runtime, MockServer};
+    use serde_json::{json, Value};
+
+    fn note(id: &str) -> Value {
+        json!({
+            "id": id,
+            "subreddit": "rust",
+            "subreddit_id": "t5_2qh1i",
+            "user": "someone",
+            "user_id": "t2_abc",
+            "operator": "spez",
+            "operator_id": "t2_1w72",
+            "created_at": 1700000000,
+            "type": "NOTE",
+            "user_note_data": {"note": "hi", "label": "SPAM_WATCH"},
+        })
+    }
+
+    #[test]
+    fn recent_keeps_notes_in_pair_order() {
+        let server = MockServer::start(|request| {
+            let notes: Vec<Value> = request
+                .param("users")
+                .unwrap()
+                .split(',')
+                .map(|user| match user {
+                    "nobody" => Value::Null,
+                    user => note(user),
+                })
+                .collect();
+            (200, json!({"mod_notes": notes}))
+        });
+        let mut session = server.session();
+        let pairs = [("rust", "a"), ("rust", "nobody"), ("python", "b")];
+
+        let notes = runtime()
+            .block_on(recent(&mut session, &pairs))
+            .ok()
+            .unwrap();
+        let ids: Vec<Option<&str>> = notes
+            .iter()
+            .map(|note| note.as_ref().map(|note| note.id.as_str()))
+            .collect();
+
+        assert_eq!(ids, vec![Some("a"), None, Some("b")]);
+        let requests = server.requests();
+        assert_eq!(requests[0].param("subreddits"), Some("rust,rust,python"));
+        assert_eq!(requests[0].param("users"), Some("a,nobody,b"));
+    }
+
+    #[test]
+    fn recent_rejects_short_answers() {
+        let server = MockServer::start(|_| (200, json!({"mod_notes": [note("a")]})));
+        let mut session = server.session();
+        let pairs = [("rust", "a"), ("rust", "b")];
+
+        match runtime().block_on(recent(&mut session, &pairs)) {
+            Err(RawrCoreError::BadJSON(data)) => assert!(data.body.contains("mod_notes")),
+            _ => panic!("expected BadJSON"),
+        }
+    }
+}
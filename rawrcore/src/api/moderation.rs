@@ -0,0 +1,319 @@
+use serde_json::{json, Value};
+
+use super::post_form_with_scope;
+use crate::{
+    auth::scopes::Scope,
+    constants,
+    errors::RawrCoreError,
+    models::{
+        fullname::{Fullname, ThingKind},
+        things::Thing,
+    },
+    pagination::ListingPaginator,
+    sessions::{RetryStrategy, Session},
+    util,
+};
+
+const MAX_STICKY_SLOT: u8 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModQueue {
+    ModQueue,
+    Reports,
+    Spam,
+    Edited,
+    Unmoderated,
+}
+impl ModQueue {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ModQueue::ModQueue => "modqueue",
+            ModQueue::Reports => "reports",
+            ModQueue::Spam => "spam",
+            ModQueue::Edited => "edited",
+            ModQueue::Unmoderated => "unmoderated",
+        }
+    }
+
+    pub fn path(&self, subreddit: &str) -> String {
+        format!("/r/{}/about/{}", subreddit, self.as_str())
+    }
+}
+
+// restricts a queue to a single kind of item
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueueOnly {
+    Links,
+    Comments,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistinguishHow {
+    Moderator,
+    Admin,
+    Special,
+    Undistinguish,
+}
+impl DistinguishHow {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DistinguishHow::Moderator => "yes",
+            DistinguishHow::Admin => "admin",
+            DistinguishHow::Special => "special",
+            DistinguishHow::Undistinguish => "no",
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RemovalReason {
+    pub reason_id: Option<String>,
+    pub mod_note: Option<String>,
+}
+
+fn ensure_kind(fullname: &Fullname, kinds: &[ThingKind], message: &'static str) -> Result<(), RawrCoreError> {
+    if kinds.contains(&fullname.kind()) {
+        return Ok(());
+    }
+
+    Err(RawrCoreError::InvalidInvocation(message))
+}
+
+fn ensure_moderatable(fullname: &Fullname) -> Result<(), RawrCoreError> {
+    ensure_kind(
+        fullname,
+        &[ThingKind::Link, ThingKind::Comment],
+        "only submissions and comments can be moderated",
+    )
+}
+
+fn ensure_submission(fullname: &Fullname) -> Result<(), RawrCoreError> {
+    ensure_kind(fullname, &[ThingKind::Link], "only submissions support this action")
+}
+
+// reddit refuses every mod action without modposts, so it is checked up
+// front even when the session doesn't check scopes
+async fn post_mod_form<R: RetryStrategy>(
+    session: &mut Session<R>,
+    path: &str,
+    data: &[(&str, String)],
+) -> Result<Value, RawrCoreError> {
+    post_form_with_scope(session, Scope::ModPosts, path, data).await
+}
+
+async fn post_id<R: RetryStrategy>(
+    session: &mut Session<R>,
+    path: &str,
+    fullname: &Fullname,
+) -> Result<(), RawrCoreError> {
+    post_mod_form(session, path, &[("id", fullname.to_string())]).await?;
+
+    Ok(())
+}
+
+pub fn queue<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    subreddit: &str,
+    queue: ModQueue,
+    only: Option<QueueOnly>,
+    limit: Option<usize>,
+) -> ListingPaginator<'s, R, Thing> {
+    let params = only.map(|only| {
+        let only = match only {
+            QueueOnly::Links => "links",
+            QueueOnly::Comments => "comments",
+        };
+
+        vec![("only".to_string(), only.to_string())]
+    });
+
+    ListingPaginator::new(session, queue.path(subreddit).as_str(), params, limit, None)
+}
+
+pub async fn approve<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+) -> Result<(), RawrCoreError> {
+    ensure_moderatable(fullname)?;
+
+    post_id(session, constants::APPROVE_PATH.as_str(), fullname).await
+}
+
+// a removal reason, when given, is attached to the item once it is removed
+pub async fn remove<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    spam: bool,
+    reason: Option<&RemovalReason>,
+) -> Result<(), RawrCoreError> {
+    ensure_moderatable(fullname)?;
+
+    let data = [("id", fullname.to_string()), ("spam", spam.to_string())];
+    post_mod_form(session, constants::REMOVE_PATH.as_str(), &data).await?;
+
+    if let Some(reason) = reason {
+        add_removal_reason(session, std::slice::from_ref(fullname), reason).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn add_removal_reason<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullnames: &[Fullname],
+    reason: &RemovalReason,
+) -> Result<(), RawrCoreError> {
+    if fullnames.is_empty() {
+        return Err(RawrCoreError::InvalidInvocation("no items were given"));
+    }
+
+    for fullname in fullnames {
+        ensure_moderatable(fullname)?;
+    }
+
+    if reason.reason_id.is_none() && reason.mod_note.is_none() {
+        return Err(RawrCoreError::InvalidInvocation(
+            "a removal reason needs a reason id or a mod note",
+        ));
+    }
+
+    // this endpoint takes its arguments as a json document in a form field
+    let body = json!({
+        "item_ids": fullnames.iter().map(|fullname| fullname.to_string()).collect::<Vec<String>>(),
+        "reason_id": reason.reason_id,
+        "mod_note": reason.mod_note,
+    });
+
+    post_mod_form(
+        session,
+        constants::REMOVAL_REASONS_PATH.as_str(),
+        &[("json", body.to_string())],
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn lock<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+) -> Result<(), RawrCoreError> {
+    ensure_moderatable(fullname)?;
+
+    post_id(session, constants::LOCK_PATH.as_str(), fullname).await
+}
+
+pub async fn unlock<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+) -> Result<(), RawrCoreError> {
+    ensure_moderatable(fullname)?;
+
+    post_id(session, constants::UNLOCK_PATH.as_str(), fullname).await
+}
+
+// `sticky` pins a distinguished top-level comment to the top of its thread
+// and is ignored for submissions
+pub async fn distinguish<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    how: DistinguishHow,
+    sticky: bool,
+) -> Result<(), RawrCoreError> {
+    ensure_moderatable(fullname)?;
+
+    let mut data = vec![
+        ("api_type", "json".to_string()),
+        ("id", fullname.to_string()),
+        ("how", how.as_str().to_string()),
+    ];
+
+    if fullname.kind() == ThingKind::Comment {
+        data.push(("sticky", sticky.to_string()));
+    }
+
+    let value = post_mod_form(session, constants::DISTINGUISH_PATH.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+// `slot` picks which of the subreddit's sticky positions to use; without
+// one reddit replaces the bottom-most sticky
+pub async fn sticky<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    state: bool,
+    slot: Option<u8>,
+) -> Result<(), RawrCoreError> {
+    ensure_submission(fullname)?;
+
+    let mut data = vec![
+        ("api_type", "json".to_string()),
+        ("id", fullname.to_string()),
+        ("state", state.to_string()),
+    ];
+
+    if let Some(slot) = slot {
+        if slot == 0 || slot > MAX_STICKY_SLOT {
+            return Err(RawrCoreError::InvalidInvocation(
+                "sticky slot must be between 1 and 4",
+            ));
+        }
+
+        data.push(("num", slot.to_string()));
+    }
+
+    let value = post_mod_form(session, constants::STICKY_PATH.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+pub async fn ignore_reports<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    ignore: bool,
+) -> Result<(), RawrCoreError> {
+    ensure_moderatable(fullname)?;
+
+    let path = if ignore {
+        constants::IGNORE_REPORTS_PATH.as_str()
+    } else {
+        constants::UNIGNORE_REPORTS_PATH.as_str()
+    };
+
+    post_id(session, path, fullname).await
+}
+
+pub async fn set_nsfw<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    nsfw: bool,
+) -> Result<(), RawrCoreError> {
+    ensure_submission(fullname)?;
+
+    let path = if nsfw {
+        constants::MARK_NSFW_PATH.as_str()
+    } else {
+        constants::UNMARK_NSFW_PATH.as_str()
+    };
+
+    post_id(session, path, fullname).await
+}
+
+pub async fn set_spoiler<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullname: &Fullname,
+    spoiler: bool,
+) -> Result<(), RawrCoreError> {
+    ensure_submission(fullname)?;
+
+    let path = if spoiler {
+        constants::SPOILER_PATH.as_str()
+    } else {
+        constants::UNSPOILER_PATH.as_str()
+    };
+
+    post_id(session, path, fullname).await
+}
@@ -0,0 +1,126 @@
+use crate::{
+    models::mod_action::{ModAction, ModActionType},
+    pagination::ListingPaginator,
+    sessions::{RetryStrategy, Session},
+    stream::ItemStream,
+};
+
+#[derive(Clone, Default)]
+pub struct ModLogFilter {
+    moderators: Vec<String>,
+    action: Option<ModActionType>,
+}
+
+impl ModLogFilter {
+    // may be called more than once to include several moderators
+    pub fn moderator(mut self, moderator: &str) -> Self {
+        self.moderators.push(moderator.to_string());
+        self
+    }
+
+    // reddit only filters on a single action type at a time
+    pub fn action(mut self, action: ModActionType) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        let mut params = vec![];
+
+        if !self.moderators.is_empty() {
+            params.push(("mod".to_string(), self.moderators.join(",")));
+        }
+
+        if let Some(action) = &self.action {
+            params.push(("type".to_string(), action.to_string()));
+        }
+
+        params
+    }
+}
+
+fn path(subreddit: &str) -> String {
+    format!("/r/{}/about/log", subreddit)
+}
+
+// "mod" as the subreddit reads the combined log of every moderated subreddit
+pub fn log<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    subreddit: &str,
+    filter: &ModLogFilter,
+    limit: Option<usize>,
+    after: Option<String>,
+) -> ListingPaginator<'s, R, ModAction> {
+    ListingPaginator::new(
+        session,
+        path(subreddit).as_str(),
+        Some(filter.params()),
+        limit,
+        after,
+    )
+}
+
+pub fn stream<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    subreddit: &str,
+    filter: &ModLogFilter,
+    skip_existing: bool,
+    pause_after: Option<usize>,
+) -> ItemStream<'s, R, ModAction> {
+    ItemStream::new(
+        session,
+        path(subreddit).as_str(),
+        Some(filter.params()),
+        skip_existing,
+        pause_after,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, MockServer};
+    use serde_json::json;
+
+    #[test]
+    fn log_sends_filter_and_paging_params() {
+        let server = MockServer::start(|_| {
+            (
+                200,
+                json!({
+                    "kind": "Listing",
+                    "data": {"after": null, "before": null, "dist": 0, "children": []},
+                }),
+            )
+        });
+        let mut session = server.session();
+        let filter = ModLogFilter::default()
+            .moderator("spez")
+            .moderator("kn0thing")
+            .action(ModActionType::RemoveLink);
+
+        let mut paginator = log(
+            &mut session,
+            "rust",
+            &filter,
+            Some(5),
+            Some("ModAction_1".to_string()),
+        );
+        assert!(test_util::runtime()
+            .block_on(paginator.next_item())
+            .is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/r/rust/about/log");
+        assert_eq!(requests[0].param("mod"), Some("spez,kn0thing"));
+        assert_eq!(requests[0].param("type"), Some("removelink"));
+        assert_eq!(requests[0].param("limit"), Some("5"));
+        assert_eq!(requests[0].param("after"), Some("ModAction_1"));
+    }
+
+    #[test]
+    fn empty_filters_send_nothing() {
+        assert!(ModLogFilter::default().params().is_empty());
+    }
+}
@@ -0,0 +1,415 @@
+use super::post_form;
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    models::modmail::{Conversation, ConversationsResponse},
+    pagination::ListingPaginator,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    stream::ItemStream,
+};
+
+const MAX_TEMP_BAN_DAYS: u16 = 999;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConversationState {
+    All,
+    New,
+    InProgress,
+    Archived,
+    Mod,
+    Notifications,
+    JoinRequests,
+    Highlighted,
+}
+impl ConversationState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ConversationState::All => "all",
+            ConversationState::New => "new",
+            ConversationState::InProgress => "inprogress",
+            ConversationState::Archived => "archived",
+            ConversationState::Mod => "mod",
+            ConversationState::Notifications => "notifications",
+            ConversationState::JoinRequests => "join_requests",
+            ConversationState::Highlighted => "highlighted",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConversationSort {
+    Recent,
+    Mod,
+    User,
+    Unread,
+}
+impl ConversationSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ConversationSort::Recent => "recent",
+            ConversationSort::Mod => "mod",
+            ConversationSort::User => "user",
+            ConversationSort::Unread => "unread",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MuteDuration {
+    ThreeDays,
+    SevenDays,
+    TwentyEightDays,
+}
+impl MuteDuration {
+    fn hours(&self) -> u16 {
+        match self {
+            MuteDuration::ThreeDays => 72,
+            MuteDuration::SevenDays => 168,
+            MuteDuration::TwentyEightDays => 672,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ConversationQuery {
+    subreddits: Vec<String>,
+    state: ConversationState,
+    sort: ConversationSort,
+    limit: Option<usize>,
+    after: Option<String>,
+}
+
+impl Default for ConversationQuery {
+    fn default() -> Self {
+        Self {
+            subreddits: vec![],
+            state: ConversationState::All,
+            sort: ConversationSort::Recent,
+            limit: None,
+            after: None,
+        }
+    }
+}
+
+impl ConversationQuery {
+    // every subreddit the account moderates is included when none are given
+    pub fn subreddit(mut self, subreddit: &str) -> Self {
+        self.subreddits.push(subreddit.to_string());
+        self
+    }
+
+    pub fn state(mut self, state: ConversationState) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn sort(mut self, sort: ConversationSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    // the most conversations to yield across all pages
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    // the id of the conversation to resume listing after
+    pub fn after(mut self, after: &str) -> Self {
+        self.after = Some(after.to_string());
+        self
+    }
+
+    // which conversations are listed, leaving out order and paging
+    fn filter_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("state", self.state.as_str().to_string())];
+
+        if !self.subreddits.is_empty() {
+            params.push(("entity", self.subreddits.join(",")));
+        }
+
+        params
+    }
+
+    // paging is left to the paginator
+    fn params(&self) -> Vec<(String, String)> {
+        let mut params: Vec<(String, String)> = self
+            .filter_params()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        params.push(("sort".to_string(), self.sort.as_str().to_string()));
+
+        params
+    }
+}
+
+fn conversation_path(id: &str, action: Option<&str>) -> String {
+    match action {
+        Some(action) => format!("{}/{}/{}", constants::MODMAIL_CONVERSATIONS_PATH.as_str(), id, action),
+        None => format!("{}/{}", constants::MODMAIL_CONVERSATIONS_PATH.as_str(), id),
+    }
+}
+
+pub fn conversations<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    query: &ConversationQuery,
+) -> ListingPaginator<'s, R, Conversation, ConversationsResponse> {
+    ListingPaginator::new(
+        session,
+        constants::MODMAIL_CONVERSATIONS_PATH.as_str(),
+        Some(query.params()),
+        query.limit,
+        query.after.clone(),
+    )
+}
+
+pub async fn conversation<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+    mark_read: bool,
+) -> Result<Conversation, RawrCoreError> {
+    let params = [("markRead", mark_read.to_string())];
+    let value = session
+        .request::<(), (), (), [(&str, String); 1]>(
+            RequestMethod::Get,
+            conversation_path(id, None).as_str(),
+            None,
+            None,
+            None,
+            Some(&params),
+            None,
+        )
+        .await?;
+
+    Conversation::from_value(value)
+}
+
+// internal replies are only visible to moderators; author-hidden replies
+// are shown to the user as coming from the subreddit
+pub async fn reply<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+    body: &str,
+    is_author_hidden: bool,
+    is_internal: bool,
+) -> Result<Conversation, RawrCoreError> {
+    if body.is_empty() {
+        return Err(RawrCoreError::InvalidInvocation("reply body must not be empty"));
+    }
+
+    let data = [
+        ("body", body.to_string()),
+        ("isAuthorHidden", is_author_hidden.to_string()),
+        ("isInternal", is_internal.to_string()),
+    ];
+    let value = post_form(session, conversation_path(id, None).as_str(), &data).await?;
+
+    Conversation::from_value(value)
+}
+
+async fn conversation_action<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+    action: &str,
+    data: &[(&str, String)],
+) -> Result<Conversation, RawrCoreError> {
+    let value = post_form(session, conversation_path(id, Some(action)).as_str(), data).await?;
+
+    Conversation::from_value(value)
+}
+
+pub async fn archive<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+) -> Result<Conversation, RawrCoreError> {
+    conversation_action(session, id, "archive", &[]).await
+}
+
+pub async fn unarchive<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+) -> Result<Conversation, RawrCoreError> {
+    conversation_action(session, id, "unarchive", &[]).await
+}
+
+pub async fn highlight<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+    highlighted: bool,
+) -> Result<Conversation, RawrCoreError> {
+    let method = if highlighted {
+        RequestMethod::Post
+    } else {
+        RequestMethod::Delete
+    };
+
+    let value = session
+        .request::<(), (), (), ()>(
+            method,
+            conversation_path(id, Some("highlight")).as_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    Conversation::from_value(value)
+}
+
+pub async fn mute<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+    duration: MuteDuration,
+) -> Result<Conversation, RawrCoreError> {
+    let data = [("num_hours", duration.hours().to_string())];
+
+    conversation_action(session, id, "mute", &data).await
+}
+
+pub async fn unmute<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+) -> Result<Conversation, RawrCoreError> {
+    conversation_action(session, id, "unmute", &[]).await
+}
+
+// temporarily bans the conversation's participant from the subreddit
+pub async fn temp_ban<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+    days: u16,
+) -> Result<Conversation, RawrCoreError> {
+    if days == 0 || days > MAX_TEMP_BAN_DAYS {
+        return Err(RawrCoreError::InvalidInvocation(
+            "temporary bans must last between 1 and 999 days",
+        ));
+    }
+
+    let data = [("duration", days.to_string())];
+
+    conversation_action(session, id, "temp_ban", &data).await
+}
+
+// approves the participant as a member of the subreddit, e.g. in answer to
+// a join request
+pub async fn approve<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+) -> Result<Conversation, RawrCoreError> {
+    conversation_action(session, id, "approve", &[]).await
+}
+
+pub async fn disapprove<R: RetryStrategy>(
+    session: &mut Session<R>,
+    id: &str,
+) -> Result<Conversation, RawrCoreError> {
+    conversation_action(session, id, "disapprove", &[]).await
+}
+
+// polls the first page of `query` forever and yields conversations that
+// have not been seen before, oldest first. the query's sort and cursor are
+// ignored; the newest conversations are always polled
+pub fn stream<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    query: &ConversationQuery,
+    skip_existing: bool,
+    pause_after: Option<usize>,
+) -> ItemStream<'s, R, Conversation, ConversationsResponse> {
+    let mut params: Vec<(String, String)> = query
+        .filter_params()
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+    params.push(("sort".to_string(), ConversationSort::Recent.as_str().to_string()));
+
+    ItemStream::new(
+        session,
+        constants::MODMAIL_CONVERSATIONS_PATH.as_str(),
+        Some(params),
+        skip_existing,
+        pause_after,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{runtime, MockServer};
+    use serde_json::{json, Value};
+
+    fn page(ids: &[&str]) -> Value {
+        let conversations: serde_json::Map<String, Value> = ids
+            .iter()
+            .map(|id| {
+                let conversation = json!({
+                    "id": id,
+                    "subject": "hi",
+                    "state": 0,
+                    "owner": {"id": "t5_2qh1i", "displayName": "rust", "type": "subreddit"},
+                });
+                (id.to_string(), conversation)
+            })
+            .collect();
+
+        json!({"conversations": conversations, "conversationIds": ids, "messages": {}})
+    }
+
+    #[test]
+    fn conversations_follow_pages_up_to_the_limit() {
+        let server = MockServer::start(|request| match request.param("after") {
+            None => (200, page(&["a", "b"])),
+            Some("b") => (200, page(&["c", "d"])),
+            Some(_) => (200, page(&[])),
+        });
+        let mut session = server.session();
+        let query = ConversationQuery::default()
+            .subreddit("rust")
+            .state(ConversationState::New)
+            .sort(ConversationSort::Unread)
+            .limit(3);
+
+        let ids: Vec<String> = conversations(&mut session, &query)
+            .into_blocking_iter()
+            .ok()
+            .unwrap()
+            .map(|conversation| conversation.ok().unwrap().id)
+            .collect();
+
+        assert_eq!(ids, vec!["a", "b", "c"]);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        for request in requests.iter() {
+            assert_eq!(request.path, constants::MODMAIL_CONVERSATIONS_PATH.as_str());
+            assert_eq!(request.param("state"), Some("new"));
+            assert_eq!(request.param("entity"), Some("rust"));
+            assert_eq!(request.param("sort"), Some("unread"));
+        }
+        assert_eq!(requests[0].param("after"), None);
+        assert_eq!(requests[1].param("after"), Some("b"));
+    }
+
+    #[test]
+    fn conversations_start_after_the_query_cursor() {
+        let server = MockServer::start(|request| match request.param("after") {
+            Some("b") => (200, page(&["c"])),
+            _ => (200, page(&[])),
+        });
+        let mut session = server.session();
+        let query = ConversationQuery::default().after("b");
+
+        runtime().block_on(async {
+            let mut paginator = conversations(&mut session, &query);
+            let first = paginator.next_item().await.unwrap().ok().unwrap();
+            assert_eq!(first.id, "c");
+        });
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("after"), Some("b"));
+        assert_eq!(requests[0].param("state"), Some("all"));
+        assert_eq!(requests[0].param("entity"), None);
+    }
+}
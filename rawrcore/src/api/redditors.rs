@@ -0,0 +1,367 @@
+use serde_json::json;
+
+use super::TimeFilter;
+use crate::{
+    constants,
+    errors::{RawrCoreError, RedditorErrorData},
+    models::{
+        multireddit::Multireddit,
+        relationship::Relationship,
+        things::{Account, Award, Thing},
+        Listing,
+    },
+    pagination::ListingPaginator,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const MAX_FRIEND_NOTE_LENGTH: usize = 300;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserListing {
+    // submissions and comments together
+    Overview,
+    Submitted,
+    Comments,
+    // upvoted, downvoted, saved and hidden are only visible to the account
+    // itself
+    Upvoted,
+    Downvoted,
+    Saved,
+    Hidden,
+    Gilded,
+}
+impl UserListing {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserListing::Overview => "overview",
+            UserListing::Submitted => "submitted",
+            UserListing::Comments => "comments",
+            UserListing::Upvoted => "upvoted",
+            UserListing::Downvoted => "downvoted",
+            UserListing::Saved => "saved",
+            UserListing::Hidden => "hidden",
+            UserListing::Gilded => "gilded",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserSort {
+    Hot,
+    New,
+    Top,
+    Controversial,
+}
+impl UserSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserSort::Hot => "hot",
+            UserSort::New => "new",
+            UserSort::Top => "top",
+            UserSort::Controversial => "controversial",
+        }
+    }
+}
+
+async fn get<R: RetryStrategy>(
+    session: &mut Session<R>,
+    path: &str,
+) -> Result<serde_json::Value, RawrCoreError> {
+    session
+        .request::<(), (), (), ()>(RequestMethod::Get, path, None, None, None, None, None)
+        .await
+}
+
+// whether reddit considers `name` taken. names stay taken after the account
+// is deleted, so this can't tell a shadowban from a deletion on its own
+async fn username_taken<R: RetryStrategy>(
+    session: &mut Session<R>,
+    name: &str,
+) -> Result<Option<bool>, RawrCoreError> {
+    let params = [("user", name.to_string())];
+    let value = session
+        .request::<(), (), (), [(&str, String); 1]>(
+            RequestMethod::Get,
+            constants::USERNAME_AVAILABLE_PATH.as_str(),
+            None,
+            None,
+            None,
+            Some(&params),
+            None,
+        )
+        .await?;
+
+    Ok(value.as_bool().map(|available| !available))
+}
+
+// suspended accounts come back without most of their fields and are
+// reported as `SuspendedUser`. an account that 404s while its name is still
+// taken is reported as `ShadowbannedUser`, though deleted accounts look the
+// same; names that were never registered stay `NotFound`
+pub async fn about<R: RetryStrategy>(
+    session: &mut Session<R>,
+    name: &str,
+) -> Result<Account, RawrCoreError> {
+    let path = format!("/user/{}/about", name);
+
+    let mut value = match get(session, path.as_str()).await {
+        Ok(value) => value,
+        Err(RawrCoreError::NotFound(not_found)) => {
+            return match username_taken(session, name).await {
+                Ok(Some(true)) => Err(RawrCoreError::ShadowbannedUser(RedditorErrorData {
+                    name: name.to_string(),
+                })),
+                _ => Err(RawrCoreError::NotFound(not_found)),
+            }
+        }
+        Err(e) => return Err(e),
+    };
+
+    if value["data"]["is_suspended"].as_bool() == Some(true) {
+        return Err(RawrCoreError::SuspendedUser(RedditorErrorData {
+            name: name.to_string(),
+        }));
+    }
+
+    util::from_json_value::<Account>(value["data"].take())
+}
+
+// `time` only applies to the top and controversial sorts. listings of
+// suspended or shadowbanned accounts fail with `NotFound`; use `about` to
+// tell those apart
+pub fn history<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    name: &str,
+    listing: UserListing,
+    sort: UserSort,
+    time: Option<TimeFilter>,
+    limit: Option<usize>,
+    after: Option<String>,
+) -> ListingPaginator<'s, R, Thing> {
+    let path = format!("/user/{}/{}", name, listing.as_str());
+    let mut params = vec![("sort".to_string(), sort.as_str().to_string())];
+
+    if let (Some(time), UserSort::Top | UserSort::Controversial) = (time, sort) {
+        params.push(("t".to_string(), time.as_str().to_string()));
+    }
+
+    ListingPaginator::new(session, path.as_str(), Some(params), limit, after)
+}
+
+pub async fn trophies<R: RetryStrategy>(
+    session: &mut Session<R>,
+    name: &str,
+) -> Result<Vec<Award>, RawrCoreError> {
+    let path = format!("/api/v1/user/{}/trophies", name);
+    let mut value = get(session, path.as_str()).await?;
+
+    // {"kind": "TrophyList", "data": {"trophies": [t6, ...]}}
+    Ok(
+        util::from_json_value::<Vec<Thing>>(value["data"]["trophies"].take())?
+            .into_iter()
+            .filter_map(|thing| match thing {
+                Thing::Award(award) => Some(*award),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+// public multireddits, or every one when `name` is the account itself
+pub async fn multireddits<R: RetryStrategy>(
+    session: &mut Session<R>,
+    name: &str,
+) -> Result<Vec<Multireddit>, RawrCoreError> {
+    let path = format!("/api/multi/user/{}", name);
+    let value = get(session, path.as_str()).await?;
+
+    Multireddit::list_from_value(value)
+}
+
+pub async fn friends<R: RetryStrategy>(
+    session: &mut Session<R>,
+) -> Result<Vec<Relationship>, RawrCoreError> {
+    let value = get(session, "/api/v1/me/friends").await?;
+
+    Ok(util::from_json_value::<Listing<Relationship>>(value)?.children)
+}
+
+// notes require reddit premium
+pub async fn add_friend<R: RetryStrategy>(
+    session: &mut Session<R>,
+    name: &str,
+    note: Option<&str>,
+) -> Result<Relationship, RawrCoreError> {
+    let mut body = json!({ "name": name });
+
+    if let Some(note) = note {
+        if note.chars().count() > MAX_FRIEND_NOTE_LENGTH {
+            return Err(RawrCoreError::InvalidInvocation(
+                "friend notes must be at most 300 characters",
+            ));
+        }
+
+        body["note"] = json!(note);
+    }
+
+    let path = format!("/api/v1/me/friends/{}", name);
+    let value = session
+        .request::<(), (), serde_json::Value, ()>(
+            RequestMethod::Put,
+            path.as_str(),
+            None,
+            None,
+            Some(&body),
+            None,
+            None,
+        )
+        .await?;
+
+    util::from_json_value::<Relationship>(value)
+}
+
+pub async fn remove_friend<R: RetryStrategy>(
+    session: &mut Session<R>,
+    name: &str,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/api/v1/me/friends/{}", name);
+
+    session
+        .request::<(), (), (), ()>(
+            RequestMethod::Delete,
+            path.as_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, MockServer};
+    use serde_json::Value;
+
+    // answers /about with `about` and /api/username_available with `available`
+    fn server(about: (u16, Value), available: bool) -> MockServer {
+        MockServer::start(move |request| match request.path.as_str() {
+            "/user/someone/about" => about.clone(),
+            "/api/username_available" => (200, json!(available)),
+            _ => (404, Value::Null),
+        })
+    }
+
+    fn about_someone(server: &MockServer) -> Result<Account, RawrCoreError> {
+        let mut session = server.session();
+
+        test_util::runtime().block_on(about(&mut session, "someone"))
+    }
+
+    fn not_found() -> (u16, Value) {
+        (404, json!({"message": "Not Found", "error": 404}))
+    }
+
+    #[test]
+    fn missing_accounts_with_taken_names_are_shadowbanned() {
+        let server = server(not_found(), false);
+
+        match about_someone(&server) {
+            Err(RawrCoreError::ShadowbannedUser(data)) => assert_eq!(data.name, "someone"),
+            _ => panic!("expected ShadowbannedUser"),
+        }
+        assert_eq!(server.requests()[1].param("user"), Some("someone"));
+    }
+
+    #[test]
+    fn missing_accounts_with_available_names_are_not_found() {
+        let server = server(not_found(), true);
+
+        assert!(matches!(
+            about_someone(&server),
+            Err(RawrCoreError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn suspended_accounts_are_reported() {
+        let server = server(
+            (
+                200,
+                json!({"kind": "t2", "data": {"name": "someone", "is_suspended": true}}),
+            ),
+            false,
+        );
+
+        match about_someone(&server) {
+            Err(RawrCoreError::SuspendedUser(data)) => assert_eq!(data.name, "someone"),
+            _ => panic!("expected SuspendedUser"),
+        }
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn active_accounts_are_returned() {
+        let server = server(
+            (
+                200,
+                json!({"kind": "t2", "data": {"id": "1w72", "name": "someone", "link_karma": 5}}),
+            ),
+            false,
+        );
+
+        let account = about_someone(&server).ok().unwrap();
+
+        assert_eq!(account.name, "someone");
+        assert_eq!(account.link_karma, 5);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    // the time filter of the first page requested by `history`
+    fn history_time(sort: UserSort) -> Option<String> {
+        let server = MockServer::start(|_| {
+            (
+                200,
+                json!({
+                    "kind": "Listing",
+                    "data": {"after": null, "before": null, "dist": 0, "children": []},
+                }),
+            )
+        });
+        let mut session = server.session();
+        let mut paginator = history(
+            &mut session,
+            "someone",
+            UserListing::Comments,
+            sort,
+            Some(TimeFilter::Week),
+            None,
+            None,
+        );
+        assert!(test_util::runtime()
+            .block_on(paginator.next_item())
+            .is_none());
+
+        let request = server.requests().remove(0);
+        assert_eq!(request.path, "/user/someone/comments");
+        assert_eq!(request.param("sort"), Some(sort.as_str()));
+
+        request.param("t").map(|time| time.to_string())
+    }
+
+    #[test]
+    fn history_only_sends_time_for_top_and_controversial() {
+        assert_eq!(history_time(UserSort::Top).as_deref(), Some("week"));
+        assert_eq!(
+            history_time(UserSort::Controversial).as_deref(),
+            Some("week")
+        );
+        assert_eq!(history_time(UserSort::Hot), None);
+        assert_eq!(history_time(UserSort::New), None);
+    }
+}
@@ -0,0 +1,245 @@
+use super::{post_form, post_form_with_scope};
+use crate::{
+    errors::RawrCoreError,
+    models::relationship::{ModPermission, Relationship, RelationshipPage, RelationshipType},
+    pagination::ListingPaginator,
+    sessions::{RetryStrategy, Session},
+    util,
+};
+
+const MAX_BAN_DAYS: u16 = 999;
+const MAX_BAN_REASON_LENGTH: usize = 100;
+const MAX_NOTE_LENGTH: usize = 300;
+
+#[derive(Clone, Default)]
+pub struct BanOptions {
+    duration: Option<u16>,
+    reason: Option<String>,
+    note: Option<String>,
+    message: Option<String>,
+}
+
+impl BanOptions {
+    // in days; bans are permanent without one
+    pub fn duration(mut self, days: u16) -> Self {
+        self.duration = Some(days);
+        self
+    }
+
+    // shown to other moderators; usually the rule that was broken
+    pub fn reason(mut self, reason: &str) -> Self {
+        self.reason = Some(reason.to_string());
+        self
+    }
+
+    // a private note for moderators
+    pub fn note(mut self, note: &str) -> Self {
+        self.note = Some(note.to_string());
+        self
+    }
+
+    // sent to the banned user
+    pub fn message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+
+    fn validate(&self) -> Result<(), RawrCoreError> {
+        if let Some(duration) = self.duration {
+            if duration == 0 || duration > MAX_BAN_DAYS {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "temporary bans must last between 1 and 999 days",
+                ));
+            }
+        }
+
+        if let Some(reason) = &self.reason {
+            if reason.chars().count() > MAX_BAN_REASON_LENGTH {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "ban reasons must be at most 100 characters",
+                ));
+            }
+        }
+
+        if let Some(note) = &self.note {
+            if note.chars().count() > MAX_NOTE_LENGTH {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "ban notes must be at most 300 characters",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn form_data(&self) -> Vec<(&'static str, String)> {
+        let mut data = vec![];
+
+        if let Some(duration) = self.duration {
+            data.push(("duration", duration.to_string()));
+        }
+
+        if let Some(reason) = &self.reason {
+            data.push(("ban_reason", reason.clone()));
+        }
+
+        if let Some(note) = &self.note {
+            data.push(("note", note.clone()));
+        }
+
+        if let Some(message) = &self.message {
+            data.push(("ban_message", message.clone()));
+        }
+
+        data
+    }
+}
+
+async fn friend<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    relationship: RelationshipType,
+    extra: Vec<(&str, String)>,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/r/{}/api/friend", subreddit);
+    let mut data = vec![
+        ("api_type", "json".to_string()),
+        ("name", user.to_string()),
+        ("type", relationship.as_str().to_string()),
+    ];
+    data.extend(extra);
+
+    let value = post_form_with_scope(session, relationship.scope(), path.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+// the users in one of the subreddit's lists, most recently added first
+pub fn list<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    subreddit: &str,
+    relationship: RelationshipType,
+    limit: Option<usize>,
+    after: Option<String>,
+) -> ListingPaginator<'s, R, Relationship, RelationshipPage> {
+    let path = relationship.listing_path(subreddit);
+
+    ListingPaginator::new(session, path.as_str(), None, limit, after)
+}
+
+// adds `user` to a list that takes nothing but an optional note. use
+// `invite_moderator` and `ban` for the lists that take more
+pub async fn add<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    relationship: RelationshipType,
+    note: Option<&str>,
+) -> Result<(), RawrCoreError> {
+    if matches!(
+        relationship,
+        RelationshipType::Moderator | RelationshipType::ModeratorInvite | RelationshipType::Banned
+    ) {
+        return Err(RawrCoreError::InvalidInvocation(
+            "moderators and bans are added with invite_moderator and ban",
+        ));
+    }
+
+    let mut extra = vec![];
+
+    if let Some(note) = note {
+        if note.chars().count() > MAX_NOTE_LENGTH {
+            return Err(RawrCoreError::InvalidInvocation("notes must be at most 300 characters"));
+        }
+
+        extra.push(("note", note.to_string()));
+    }
+
+    friend(session, subreddit, user, relationship, extra).await
+}
+
+// removing a `ModeratorInvite` revokes a pending invite
+pub async fn remove<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    relationship: RelationshipType,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/r/{}/api/unfriend", subreddit);
+    let data = [
+        ("api_type", "json".to_string()),
+        ("name", user.to_string()),
+        ("type", relationship.as_str().to_string()),
+    ];
+
+    let value = post_form_with_scope(session, relationship.scope(), path.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+pub async fn ban<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    options: &BanOptions,
+) -> Result<(), RawrCoreError> {
+    options.validate()?;
+
+    friend(session, subreddit, user, RelationshipType::Banned, options.form_data()).await
+}
+
+// moderators join by accepting an invite; `permissions` applies once they do
+pub async fn invite_moderator<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    permissions: &[ModPermission],
+) -> Result<(), RawrCoreError> {
+    let extra = vec![("permissions", ModPermission::permission_string(permissions))];
+
+    friend(session, subreddit, user, RelationshipType::ModeratorInvite, extra).await
+}
+
+// changes the permissions of a moderator, or of a pending invite when
+// `invited` is set
+pub async fn set_moderator_permissions<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    user: &str,
+    permissions: &[ModPermission],
+    invited: bool,
+) -> Result<(), RawrCoreError> {
+    let relationship = if invited {
+        RelationshipType::ModeratorInvite
+    } else {
+        RelationshipType::Moderator
+    };
+
+    let path = format!("/r/{}/api/setpermissions", subreddit);
+    let data = [
+        ("api_type", "json".to_string()),
+        ("name", user.to_string()),
+        ("type", relationship.as_str().to_string()),
+        ("permissions", ModPermission::permission_string(permissions)),
+    ];
+
+    let value = post_form(session, path.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+pub async fn accept_moderator_invite<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/r/{}/api/accept_moderator_invite", subreddit);
+
+    let value = post_form(session, path.as_str(), &[("api_type", "json".to_string())]).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use serde::Deserialize;
+
+use super::post_form;
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    models::rules::{RemovalReasonsResponse, Rule, RuleKind, RulesResponse, SubredditRemovalReason},
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const MAX_RULES: usize = 15;
+const MAX_SHORT_NAME_LENGTH: usize = 100;
+const MAX_DESCRIPTION_LENGTH: usize = 500;
+const MAX_VIOLATION_REASON_LENGTH: usize = 100;
+const MAX_REMOVAL_REASON_TITLE_LENGTH: usize = 50;
+
+#[derive(Clone)]
+pub struct RuleDraft {
+    pub short_name: String,
+    pub kind: RuleKind,
+    pub description: String,
+    // shown as the report reason; defaults to the short name
+    pub violation_reason: Option<String>,
+}
+impl RuleDraft {
+    fn validate(&self) -> Result<(), RawrCoreError> {
+        let short_name_length = self.short_name.chars().count();
+        if short_name_length == 0 || short_name_length > MAX_SHORT_NAME_LENGTH {
+            return Err(RawrCoreError::InvalidInvocation(
+                "rule short names must be between 1 and 100 characters",
+            ));
+        }
+
+        if self.description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            return Err(RawrCoreError::InvalidInvocation(
+                "rule descriptions must be at most 500 characters",
+            ));
+        }
+
+        if let Some(violation_reason) = &self.violation_reason {
+            if violation_reason.chars().count() > MAX_VIOLATION_REASON_LENGTH {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "rule violation reasons must be at most 100 characters",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn form_data(&self, subreddit: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("api_type", "json".to_string()),
+            ("r", subreddit.to_string()),
+            ("short_name", self.short_name.clone()),
+            ("kind", self.kind.as_str().to_string()),
+            ("description", self.description.clone()),
+            (
+                "violation_reason",
+                self.violation_reason
+                    .clone()
+                    .unwrap_or_else(|| self.short_name.clone()),
+            ),
+        ]
+    }
+}
+
+#[derive(Deserialize)]
+struct CreatedRemovalReason {
+    id: String,
+}
+
+// rules in priority order
+pub async fn rules<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+) -> Result<Vec<Rule>, RawrCoreError> {
+    let path = format!("/r/{}/about/rules", subreddit);
+    let value = session
+        .request::<(), (), (), ()>(RequestMethod::Get, path.as_str(), None, None, None, None, None)
+        .await?;
+    let mut rules = util::from_json_value::<RulesResponse>(value)?.rules;
+    rules.sort_by_key(|rule| rule.priority);
+
+    Ok(rules)
+}
+
+pub async fn add_rule<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    rule: &RuleDraft,
+) -> Result<(), RawrCoreError> {
+    rule.validate()?;
+
+    if rules(session, subreddit).await?.len() >= MAX_RULES {
+        return Err(RawrCoreError::InvalidInvocation(
+            "subreddits can have at most 15 rules",
+        ));
+    }
+
+    let value = post_form(session, constants::ADD_RULE_PATH.as_str(), &rule.form_data(subreddit)).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+pub async fn update_rule<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    old_short_name: &str,
+    rule: &RuleDraft,
+) -> Result<(), RawrCoreError> {
+    rule.validate()?;
+
+    let mut data = rule.form_data(subreddit);
+    data.push(("old_short_name", old_short_name.to_string()));
+
+    let value = post_form(session, constants::UPDATE_RULE_PATH.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+pub async fn remove_rule<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    short_name: &str,
+) -> Result<(), RawrCoreError> {
+    let data = [
+        ("api_type", "json".to_string()),
+        ("r", subreddit.to_string()),
+        ("short_name", short_name.to_string()),
+    ];
+
+    let value = post_form(session, constants::REMOVE_RULE_PATH.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+// `short_names` must list every rule of the subreddit exactly once
+pub async fn reorder_rules<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    short_names: &[&str],
+) -> Result<(), RawrCoreError> {
+    let current = rules(session, subreddit).await?;
+
+    let mut given: Vec<&str> = short_names.to_vec();
+    let mut existing: Vec<&str> = current.iter().map(|rule| rule.short_name.as_str()).collect();
+    given.sort_unstable();
+    existing.sort_unstable();
+
+    if given != existing {
+        return Err(RawrCoreError::InvalidInvocation(
+            "the new rule order must contain every existing rule exactly once",
+        ));
+    }
+
+    let data = [
+        ("api_type", "json".to_string()),
+        ("r", subreddit.to_string()),
+        ("new_rule_order", short_names.join(",")),
+    ];
+
+    let value = post_form(session, constants::REORDER_RULES_PATH.as_str(), &data).await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+fn removal_reasons_path(subreddit: &str, id: Option<&str>) -> String {
+    match id {
+        Some(id) => format!("/api/v1/{}/removal_reasons/{}", subreddit, id),
+        None => format!("/api/v1/{}/removal_reasons", subreddit),
+    }
+}
+
+fn validate_removal_reason(title: &str, message: &str) -> Result<(), RawrCoreError> {
+    let title_length = title.chars().count();
+    if title_length == 0 || title_length > MAX_REMOVAL_REASON_TITLE_LENGTH {
+        return Err(RawrCoreError::InvalidInvocation(
+            "removal reason titles must be between 1 and 50 characters",
+        ));
+    }
+
+    if message.is_empty() {
+        return Err(RawrCoreError::InvalidInvocation(
+            "removal reason messages must not be empty",
+        ));
+    }
+
+    Ok(())
+}
+
+// removal reasons in the order moderators see them
+pub async fn removal_reasons<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+) -> Result<Vec<SubredditRemovalReason>, RawrCoreError> {
+    let value = session
+        .request::<(), (), (), ()>(
+            RequestMethod::Get,
+            removal_reasons_path(subreddit, None).as_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    let mut response = util::from_json_value::<RemovalReasonsResponse>(value)?;
+
+    Ok(response
+        .order
+        .iter()
+        .filter_map(|id| response.data.remove(id))
+        .collect())
+}
+
+// returns the id of the new removal reason
+pub async fn add_removal_reason<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    title: &str,
+    message: &str,
+) -> Result<String, RawrCoreError> {
+    validate_removal_reason(title, message)?;
+
+    let data = [("title", title.to_string()), ("message", message.to_string())];
+    let value = post_form(session, removal_reasons_path(subreddit, None).as_str(), &data).await?;
+
+    Ok(util::from_json_value::<CreatedRemovalReason>(value)?.id)
+}
+
+pub async fn update_removal_reason<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    id: &str,
+    title: &str,
+    message: &str,
+) -> Result<(), RawrCoreError> {
+    validate_removal_reason(title, message)?;
+
+    let data = [("title", title.to_string()), ("message", message.to_string())];
+    session
+        .request::<[(&str, String); 2], (), (), ()>(
+            RequestMethod::Put,
+            removal_reasons_path(subreddit, Some(id)).as_str(),
+            Some(&data),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_removal_reason<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    id: &str,
+) -> Result<(), RawrCoreError> {
+    session
+        .request::<(), (), (), ()>(
+            RequestMethod::Delete,
+            removal_reasons_path(subreddit, Some(id)).as_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,358 @@
+use super::{join_fullnames, TimeFilter};
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    models::{fullname::Fullname, things::Thing, Listing},
+    pagination::ListingPaginator,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const INFO_BATCH_SIZE: usize = 100;
+const MAX_QUERY_LENGTH: usize = 512;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchSort {
+    Relevance,
+    Hot,
+    Top,
+    New,
+    Comments,
+}
+impl SearchSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SearchSort::Relevance => "relevance",
+            SearchSort::Hot => "hot",
+            SearchSort::Top => "top",
+            SearchSort::New => "new",
+            SearchSort::Comments => "comments",
+        }
+    }
+}
+
+// builds a lucene query from free text and field filters, e.g.
+// `SearchQuery::new("rust").author("spez").self_posts(true)` searches for
+// `rust author:spez self:yes`. filter values with spaces are quoted; values
+// containing quotes can't be searched for and fail the search
+#[derive(Clone)]
+pub struct SearchQuery {
+    terms: String,
+    filters: Vec<String>,
+    sort: SearchSort,
+    time: TimeFilter,
+    has_quoted_value: bool,
+}
+
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+impl SearchQuery {
+    pub fn new(terms: &str) -> Self {
+        Self {
+            terms: terms.trim().to_string(),
+            filters: vec![],
+            sort: SearchSort::Relevance,
+            time: TimeFilter::All,
+            has_quoted_value: false,
+        }
+    }
+
+    fn filter(mut self, field: &str, value: &str) -> Self {
+        self.has_quoted_value |= value.contains('"');
+        self.filters.push(format!("{}:{}", field, quote(value)));
+        self
+    }
+
+    pub fn author(self, author: &str) -> Self {
+        self.filter("author", author)
+    }
+
+    pub fn flair(self, flair: &str) -> Self {
+        self.filter("flair", flair)
+    }
+
+    // only self posts when set, only link posts otherwise
+    pub fn self_posts(mut self, self_posts: bool) -> Self {
+        let value = if self_posts { "yes" } else { "no" };
+        self.filters.push(format!("self:{}", value));
+        self
+    }
+
+    pub fn subreddit(self, subreddit: &str) -> Self {
+        self.filter("subreddit", subreddit)
+    }
+
+    pub fn site(self, domain: &str) -> Self {
+        self.filter("site", domain)
+    }
+
+    pub fn title(self, title: &str) -> Self {
+        self.filter("title", title)
+    }
+
+    pub fn sort(mut self, sort: SearchSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn time(mut self, time: TimeFilter) -> Self {
+        self.time = time;
+        self
+    }
+
+    pub fn query(&self) -> String {
+        let mut parts = vec![];
+
+        if !self.terms.is_empty() {
+            parts.push(self.terms.clone());
+        }
+
+        parts.extend(self.filters.iter().cloned());
+
+        parts.join(" ")
+    }
+
+    fn params(&self, restrict_sr: bool) -> Result<Vec<(String, String)>, RawrCoreError> {
+        if self.has_quoted_value {
+            return Err(RawrCoreError::InvalidInvocation(
+                "search filter values must not contain quotes",
+            ));
+        }
+
+        let query = self.query();
+        let query_length = query.chars().count();
+
+        if query_length == 0 || query_length > MAX_QUERY_LENGTH {
+            return Err(RawrCoreError::InvalidInvocation(
+                "search queries must be between 1 and 512 characters",
+            ));
+        }
+
+        let mut params = vec![
+            ("q".to_string(), query),
+            ("sort".to_string(), self.sort.as_str().to_string()),
+            ("t".to_string(), self.time.as_str().to_string()),
+            ("syntax".to_string(), "lucene".to_string()),
+        ];
+
+        if restrict_sr {
+            params.push(("restrict_sr".to_string(), "true".to_string()));
+        }
+
+        Ok(params)
+    }
+}
+
+// searches every subreddit, or only `subreddit` when given
+pub fn search<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    subreddit: Option<&str>,
+    query: &SearchQuery,
+    limit: Option<usize>,
+    after: Option<String>,
+) -> Result<ListingPaginator<'s, R, Thing>, RawrCoreError> {
+    let params = query.params(subreddit.is_some())?;
+    let path = match subreddit {
+        Some(subreddit) => format!("/r/{}/search", subreddit),
+        None => constants::SEARCH_PATH.to_string(),
+    };
+
+    Ok(ListingPaginator::new(session, path.as_str(), Some(params), limit, after))
+}
+
+async fn info_batch<R: RetryStrategy>(
+    session: &mut Session<R>,
+    params: &[(&str, String)],
+) -> Result<Vec<Thing>, RawrCoreError> {
+    let value = session
+        .request::<(), (), (), [(&str, String)]>(
+            RequestMethod::Get,
+            constants::INFO_PATH.as_str(),
+            None,
+            None,
+            None,
+            Some(params),
+            None,
+        )
+        .await?;
+
+    Ok(util::from_json_value::<Listing<Thing>>(value)?.children)
+}
+
+// looks up any mix of submissions, comments and subreddits, 100 at a time.
+// things that no longer exist are left out
+pub async fn info<R: RetryStrategy>(
+    session: &mut Session<R>,
+    fullnames: &[Fullname],
+) -> Result<Vec<Thing>, RawrCoreError> {
+    let mut things = Vec::with_capacity(fullnames.len());
+
+    for batch in fullnames.chunks(INFO_BATCH_SIZE) {
+        things.extend(info_batch(session, &[("id", join_fullnames(batch))]).await?);
+    }
+
+    Ok(things)
+}
+
+// subreddits by name, 100 at a time
+pub async fn info_subreddits<R: RetryStrategy>(
+    session: &mut Session<R>,
+    names: &[&str],
+) -> Result<Vec<Thing>, RawrCoreError> {
+    let mut things = Vec::with_capacity(names.len());
+
+    for batch in names.chunks(INFO_BATCH_SIZE) {
+        things.extend(info_batch(session, &[("sr_name", batch.join(","))]).await?);
+    }
+
+    Ok(things)
+}
+
+// the submissions linking to each of `urls`. reddit takes a single url per
+// request, so one request is made per url
+pub async fn info_urls<R: RetryStrategy>(
+    session: &mut Session<R>,
+    urls: &[&str],
+) -> Result<Vec<Thing>, RawrCoreError> {
+    let mut things = vec![];
+
+    for url in urls {
+        things.extend(info_batch(session, &[("url", url.to_string())]).await?);
+    }
+
+    Ok(things)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::fullname::ThingKind,
+        test_util::{self, MockServer},
+    };
+    use serde_json::{json, Value};
+
+    fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        params
+            .iter()
+            .find(|(key, _)| key.eq(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn empty_listing() -> Value {
+        json!({
+            "kind": "Listing",
+            "data": {"after": null, "before": null, "dist": 0, "children": []},
+        })
+    }
+
+    #[test]
+    fn filters_follow_the_terms() {
+        let query = SearchQuery::new(" rust ")
+            .author("spez")
+            .flair("help")
+            .self_posts(true);
+        assert_eq!(query.query(), "rust author:spez flair:help self:yes");
+
+        let query = SearchQuery::new("").self_posts(false);
+        assert_eq!(query.query(), "self:no");
+    }
+
+    #[test]
+    fn values_with_spaces_are_quoted() {
+        let query = SearchQuery::new("rust")
+            .flair("Help Wanted")
+            .title("async traits");
+
+        assert_eq!(
+            query.query(),
+            r#"rust flair:"Help Wanted" title:"async traits""#
+        );
+    }
+
+    #[test]
+    fn values_with_quotes_are_rejected() {
+        let query = SearchQuery::new("rust").title(r#"the "best" crate"#);
+
+        assert!(matches!(
+            query.params(false),
+            Err(RawrCoreError::InvalidInvocation(_))
+        ));
+    }
+
+    #[test]
+    fn queries_must_be_between_1_and_512_characters() {
+        assert!(SearchQuery::new(" ").params(false).is_err());
+        assert!(SearchQuery::new("a".repeat(513).as_str())
+            .params(false)
+            .is_err());
+
+        let params = SearchQuery::new("a".repeat(512).as_str())
+            .params(false)
+            .ok()
+            .unwrap();
+        assert_eq!(param(&params, "q").map(|q| q.len()), Some(512));
+        assert_eq!(param(&params, "syntax"), Some("lucene"));
+        assert_eq!(param(&params, "sort"), Some("relevance"));
+        assert_eq!(param(&params, "t"), Some("all"));
+    }
+
+    #[test]
+    fn restrict_sr_is_only_sent_within_a_subreddit() {
+        let server = MockServer::start(|_| (200, empty_listing()));
+        let mut session = server.session();
+        let query = SearchQuery::new("rust");
+        let runtime = test_util::runtime();
+
+        let mut paginator = search(&mut session, Some("rust"), &query, None, None)
+            .ok()
+            .unwrap();
+        assert!(runtime.block_on(paginator.next_item()).is_none());
+
+        let mut paginator = search(&mut session, None, &query, None, None).ok().unwrap();
+        assert!(runtime.block_on(paginator.next_item()).is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/r/rust/search");
+        assert_eq!(requests[0].param("restrict_sr"), Some("true"));
+        assert_eq!(requests[1].path, "/search");
+        assert_eq!(requests[1].param("restrict_sr"), None);
+    }
+
+    #[test]
+    fn info_looks_things_up_100_at_a_time() {
+        let server = MockServer::start(|_| (200, empty_listing()));
+        let mut session = server.session();
+        let fullnames: Vec<Fullname> = (1..=250)
+            .map(|n| Fullname::from_id_number(ThingKind::Link, n))
+            .collect();
+
+        let things = test_util::runtime()
+            .block_on(info(&mut session, &fullnames))
+            .ok()
+            .unwrap();
+        assert!(things.is_empty());
+
+        let batches: Vec<Vec<String>> = server
+            .requests()
+            .iter()
+            .map(|request| {
+                let ids = request.param("id").unwrap_or_default();
+                ids.split(',').map(|id| id.to_string()).collect()
+            })
+            .collect();
+
+        assert_eq!(
+            batches.iter().map(|ids| ids.len()).collect::<Vec<usize>>(),
+            [100, 100, 50]
+        );
+        assert_eq!(batches[0][0], "t3_1");
+        assert_eq!(batches[2][49], fullnames[249].to_string());
+    }
+}
@@ -0,0 +1,83 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    models::subreddit_settings::{SettingChange, SubredditSettings},
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+pub async fn fetch<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+) -> Result<SubredditSettings, RawrCoreError> {
+    let path = format!("/r/{}/about/edit", subreddit);
+    let value = session
+        .request::<(), (), (), ()>(RequestMethod::Get, path.as_str(), None, None, None, None, None)
+        .await?;
+
+    SubredditSettings::from_value(value)
+}
+
+pub async fn submit<R: RetryStrategy>(
+    session: &mut Session<R>,
+    settings: &SubredditSettings,
+) -> Result<(), RawrCoreError> {
+    let value = session
+        .request::<Vec<(String, String)>, (), (), ()>(
+            RequestMethod::Post,
+            constants::SITE_ADMIN_PATH.as_str(),
+            Some(&settings.form_data()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    util::json_api_data(value)?;
+
+    Ok(())
+}
+
+// fetches the current settings, applies `update` to them and submits the
+// result only when something changed. returns what changed
+pub async fn update<R, F>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    update: F,
+) -> Result<Vec<SettingChange>, RawrCoreError>
+where
+    R: RetryStrategy,
+    F: FnOnce(&mut SubredditSettings),
+{
+    let current = fetch(session, subreddit).await?;
+    let mut updated = current.clone();
+    update(&mut updated);
+
+    let changes = current.diff(&updated);
+
+    if !changes.is_empty() {
+        submit(session, &updated).await?;
+    }
+
+    Ok(changes)
+}
+
+// like `update`, with the changes given as a partial settings document
+pub async fn apply_patch<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    patch: &Map<String, Value>,
+) -> Result<Vec<SettingChange>, RawrCoreError> {
+    let current = fetch(session, subreddit).await?;
+    let updated = current.patched(patch)?;
+
+    let changes = current.diff(&updated);
+
+    if !changes.is_empty() {
+        submit(session, &updated).await?;
+    }
+
+    Ok(changes)
+}
@@ -0,0 +1,522 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    api::media::{self, UploadedMedia},
+    constants,
+    errors::{BadJSONData, RawrCoreError},
+    models::fullname::Fullname,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    upload::MediaKind,
+    util,
+};
+
+const MAX_TITLE_LENGTH: usize = 300;
+const MIN_POLL_OPTIONS: usize = 2;
+const MAX_POLL_OPTIONS: usize = 6;
+const MAX_POLL_DURATION_DAYS: u8 = 7;
+const MIN_GALLERY_ITEMS: usize = 2;
+const MAX_GALLERY_ITEMS: usize = 20;
+
+#[derive(Serialize, Clone)]
+pub struct GalleryItem {
+    pub media_id: String,
+    pub caption: String,
+    pub outbound_url: String,
+}
+impl GalleryItem {
+    pub fn new(media_id: &str, caption: Option<&str>, outbound_url: Option<&str>) -> Self {
+        Self {
+            media_id: media_id.to_string(),
+            caption: caption.unwrap_or_default().to_string(),
+            outbound_url: outbound_url.unwrap_or_default().to_string(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum SubmissionKind {
+    SelfPost { text: String },
+    Link { url: String, resubmit: bool },
+    Crosspost { crosspost_fullname: Fullname },
+    Poll { text: String, options: Vec<String>, duration_days: u8 },
+    Gallery { items: Vec<GalleryItem> },
+    Image { media: UploadedMedia },
+    Video { media: UploadedMedia, poster: UploadedMedia, videogif: bool },
+}
+
+#[derive(Clone)]
+pub struct SubmissionBuilder {
+    subreddit: String,
+    title: String,
+    kind: SubmissionKind,
+    flair_id: Option<String>,
+    flair_text: Option<String>,
+    nsfw: bool,
+    spoiler: bool,
+    send_replies: bool,
+}
+
+impl SubmissionBuilder {
+    fn new(subreddit: &str, title: &str, kind: SubmissionKind) -> Self {
+        Self {
+            subreddit: subreddit.to_string(),
+            title: title.to_string(),
+            kind,
+            flair_id: None,
+            flair_text: None,
+            nsfw: false,
+            spoiler: false,
+            send_replies: true,
+        }
+    }
+
+    pub fn text(subreddit: &str, title: &str, text: &str) -> Self {
+        Self::new(subreddit, title, SubmissionKind::SelfPost { text: text.to_string() })
+    }
+
+    pub fn link(subreddit: &str, title: &str, url: &str) -> Self {
+        Self::new(
+            subreddit,
+            title,
+            SubmissionKind::Link {
+                url: url.to_string(),
+                resubmit: true,
+            },
+        )
+    }
+
+    pub fn crosspost(subreddit: &str, title: &str, crosspost_fullname: &Fullname) -> Self {
+        Self::new(
+            subreddit,
+            title,
+            SubmissionKind::Crosspost {
+                crosspost_fullname: crosspost_fullname.clone(),
+            },
+        )
+    }
+
+    pub fn poll(subreddit: &str, title: &str, text: &str, options: Vec<String>, duration_days: u8) -> Self {
+        Self::new(
+            subreddit,
+            title,
+            SubmissionKind::Poll {
+                text: text.to_string(),
+                options,
+                duration_days,
+            },
+        )
+    }
+
+    pub fn gallery(subreddit: &str, title: &str, items: Vec<GalleryItem>) -> Self {
+        Self::new(subreddit, title, SubmissionKind::Gallery { items })
+    }
+
+    pub fn image(subreddit: &str, title: &str, media: &UploadedMedia) -> Self {
+        Self::new(subreddit, title, SubmissionKind::Image { media: media.clone() })
+    }
+
+    // videogif posts are played on a loop without sound
+    pub fn video(
+        subreddit: &str,
+        title: &str,
+        media: &UploadedMedia,
+        poster: &UploadedMedia,
+        videogif: bool,
+    ) -> Self {
+        Self::new(
+            subreddit,
+            title,
+            SubmissionKind::Video {
+                media: media.clone(),
+                poster: poster.clone(),
+                videogif,
+            },
+        )
+    }
+
+    pub fn flair(mut self, flair_id: Option<&str>, flair_text: Option<&str>) -> Self {
+        self.flair_id = flair_id.map(|id| id.to_string());
+        self.flair_text = flair_text.map(|text| text.to_string());
+        self
+    }
+
+    pub fn nsfw(mut self, nsfw: bool) -> Self {
+        self.nsfw = nsfw;
+        self
+    }
+
+    pub fn spoiler(mut self, spoiler: bool) -> Self {
+        self.spoiler = spoiler;
+        self
+    }
+
+    pub fn send_replies(mut self, send_replies: bool) -> Self {
+        self.send_replies = send_replies;
+        self
+    }
+
+    // only meaningful for link posts; when false reddit rejects urls that
+    // were already submitted to the subreddit
+    pub fn resubmit(mut self, resubmit: bool) -> Self {
+        if let SubmissionKind::Link { resubmit: r, .. } = &mut self.kind {
+            *r = resubmit;
+        }
+        self
+    }
+
+    fn validate(&self) -> Result<(), RawrCoreError> {
+        if self.subreddit.is_empty() {
+            return Err(RawrCoreError::InvalidInvocation("subreddit must not be empty"));
+        }
+
+        let title_length = self.title.chars().count();
+        if title_length == 0 || title_length > MAX_TITLE_LENGTH {
+            return Err(RawrCoreError::InvalidInvocation(
+                "title must be between 1 and 300 characters",
+            ));
+        }
+
+        match &self.kind {
+            SubmissionKind::Poll { options, duration_days, .. } => {
+                if options.len() < MIN_POLL_OPTIONS || options.len() > MAX_POLL_OPTIONS {
+                    return Err(RawrCoreError::InvalidInvocation(
+                        "polls must have between 2 and 6 options",
+                    ));
+                }
+
+                if *duration_days == 0 || *duration_days > MAX_POLL_DURATION_DAYS {
+                    return Err(RawrCoreError::InvalidInvocation(
+                        "poll duration must be between 1 and 7 days",
+                    ));
+                }
+            }
+            SubmissionKind::Gallery { items }
+                if items.len() < MIN_GALLERY_ITEMS || items.len() > MAX_GALLERY_ITEMS =>
+            {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "galleries must have between 2 and 20 items",
+                ));
+            }
+            SubmissionKind::Image { media } if media.kind != MediaKind::Image => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "image posts must be given an uploaded image",
+                ));
+            }
+            SubmissionKind::Video { media, poster, .. }
+                if media.kind != MediaKind::Video || poster.kind != MediaKind::Image =>
+            {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "video posts must be given an uploaded video and poster image",
+                ));
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn form_data(&self) -> Vec<(&'static str, String)> {
+        let mut data = vec![
+            ("api_type", "json".to_string()),
+            ("sr", self.subreddit.clone()),
+            ("title", self.title.clone()),
+            ("nsfw", self.nsfw.to_string()),
+            ("spoiler", self.spoiler.to_string()),
+            ("sendreplies", self.send_replies.to_string()),
+        ];
+
+        match &self.kind {
+            SubmissionKind::SelfPost { text } => {
+                data.push(("kind", "self".to_string()));
+                data.push(("text", text.clone()));
+            }
+            SubmissionKind::Link { url, resubmit } => {
+                data.push(("kind", "link".to_string()));
+                data.push(("url", url.clone()));
+                data.push(("resubmit", resubmit.to_string()));
+            }
+            SubmissionKind::Crosspost { crosspost_fullname } => {
+                data.push(("kind", "crosspost".to_string()));
+                data.push(("crosspost_fullname", crosspost_fullname.to_string()));
+            }
+            SubmissionKind::Image { media } => {
+                data.push(("kind", "image".to_string()));
+                data.push(("url", media.url.clone()));
+            }
+            SubmissionKind::Video { media, poster, videogif } => {
+                let kind = if *videogif { "videogif" } else { "video" };
+
+                data.push(("kind", kind.to_string()));
+                data.push(("url", media.url.clone()));
+                data.push(("video_poster_url", poster.url.clone()));
+            }
+            _ => (),
+        }
+
+        if let Some(flair_id) = &self.flair_id {
+            data.push(("flair_id", flair_id.clone()));
+        }
+
+        if let Some(flair_text) = &self.flair_text {
+            data.push(("flair_text", flair_text.clone()));
+        }
+
+        data
+    }
+
+    fn json_body(&self) -> Value {
+        let mut body = json!({
+            "api_type": "json",
+            "sr": self.subreddit,
+            "title": self.title,
+            "nsfw": self.nsfw,
+            "spoiler": self.spoiler,
+            "sendreplies": self.send_replies,
+            "flair_id": self.flair_id,
+            "flair_text": self.flair_text,
+        });
+
+        match &self.kind {
+            SubmissionKind::Poll { text, options, duration_days } => {
+                body["text"] = json!(text);
+                body["options"] = json!(options);
+                body["duration"] = json!(duration_days);
+            }
+            SubmissionKind::Gallery { items } => {
+                body["items"] = json!(items);
+                body["show_error_list"] = json!(true);
+            }
+            _ => (),
+        }
+
+        body
+    }
+
+    pub async fn submit<R: RetryStrategy>(&self, session: &mut Session<R>) -> Result<Fullname, RawrCoreError> {
+        self.validate()?;
+
+        let value = match &self.kind {
+            SubmissionKind::Poll { .. } | SubmissionKind::Gallery { .. } => {
+                let path = match &self.kind {
+                    SubmissionKind::Poll { .. } => constants::SUBMIT_POLL_PATH.as_str(),
+                    _ => constants::SUBMIT_GALLERY_PATH.as_str(),
+                };
+
+                session
+                    .request::<(), (), Value, ()>(
+                        RequestMethod::Post,
+                        path,
+                        None,
+                        None,
+                        Some(&self.json_body()),
+                        None,
+                        None,
+                    )
+                    .await?
+            }
+            _ => {
+                session
+                    .request::<Vec<(&str, String)>, (), (), ()>(
+                        RequestMethod::Post,
+                        constants::SUBMIT_PATH.as_str(),
+                        Some(&self.form_data()),
+                        None,
+                        None,
+                        None,
+                        None,
+                    )
+                    .await?
+            }
+        };
+
+        let data = util::json_api_data(value)?;
+
+        if matches!(self.kind, SubmissionKind::Image { .. } | SubmissionKind::Video { .. }) {
+            return media::wait_for_media_post(data).await;
+        }
+
+        submitted_fullname(data)
+    }
+}
+
+// /api/submit puts the fullname in `name`, the json endpoints in `id`
+fn submitted_fullname(data: Value) -> Result<Fullname, RawrCoreError> {
+    let fullname = match data["name"].is_string() {
+        true => &data["name"],
+        false => &data["id"],
+    };
+
+    match Fullname::deserialize(fullname) {
+        Ok(fullname) => Ok(fullname),
+        Err(e) => Err(RawrCoreError::BadJSON(BadJSONData {
+            body: data.to_string(),
+            original_error: e,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, MockServer};
+
+    fn media(kind: MediaKind, url: &str) -> UploadedMedia {
+        UploadedMedia {
+            asset_id: "abc".to_string(),
+            url: url.to_string(),
+            kind,
+            websocket_url: None,
+        }
+    }
+
+    fn field<'a>(data: &'a [(&str, String)], name: &str) -> Option<&'a str> {
+        data.iter()
+            .find(|(key, _)| key.eq(&name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn options(count: usize) -> Vec<String> {
+        (0..count).map(|n| n.to_string()).collect()
+    }
+
+    fn gallery(count: usize) -> Vec<GalleryItem> {
+        (0..count).map(|n| GalleryItem::new(n.to_string().as_str(), None, None)).collect()
+    }
+
+    fn rejected(builder: SubmissionBuilder) -> bool {
+        matches!(builder.validate(), Err(RawrCoreError::InvalidInvocation(_)))
+    }
+
+    #[test]
+    fn titles_must_be_between_1_and_300_characters() {
+        assert!(rejected(SubmissionBuilder::text("rust", "", "")));
+        assert!(rejected(SubmissionBuilder::text("rust", "a".repeat(301).as_str(), "")));
+        assert!(rejected(SubmissionBuilder::text("", "title", "")));
+
+        // counted in characters rather than bytes
+        assert!(SubmissionBuilder::text("rust", "é".repeat(300).as_str(), "").validate().is_ok());
+    }
+
+    #[test]
+    fn polls_are_limited_in_options_and_duration() {
+        assert!(rejected(SubmissionBuilder::poll("rust", "t", "", options(1), 3)));
+        assert!(rejected(SubmissionBuilder::poll("rust", "t", "", options(7), 3)));
+        assert!(rejected(SubmissionBuilder::poll("rust", "t", "", options(2), 0)));
+        assert!(rejected(SubmissionBuilder::poll("rust", "t", "", options(2), 8)));
+
+        assert!(SubmissionBuilder::poll("rust", "t", "", options(6), 7).validate().is_ok());
+    }
+
+    #[test]
+    fn galleries_are_limited_in_items() {
+        assert!(rejected(SubmissionBuilder::gallery("rust", "t", gallery(1))));
+        assert!(rejected(SubmissionBuilder::gallery("rust", "t", gallery(21))));
+
+        assert!(SubmissionBuilder::gallery("rust", "t", gallery(20)).validate().is_ok());
+    }
+
+    #[test]
+    fn media_posts_need_matching_uploads() {
+        let image = media(MediaKind::Image, "https://i/a.png");
+        let video = media(MediaKind::Video, "https://v/a.mp4");
+
+        assert!(rejected(SubmissionBuilder::image("rust", "t", &video)));
+        assert!(rejected(SubmissionBuilder::video("rust", "t", &image, &image, false)));
+        assert!(rejected(SubmissionBuilder::video("rust", "t", &video, &video, false)));
+
+        assert!(SubmissionBuilder::image("rust", "t", &image).validate().is_ok());
+        assert!(SubmissionBuilder::video("rust", "t", &video, &image, false).validate().is_ok());
+    }
+
+    #[test]
+    fn form_data_names_each_kind() {
+        let text = SubmissionBuilder::text("rust", "t", "body").form_data();
+        assert_eq!(field(&text, "kind"), Some("self"));
+        assert_eq!(field(&text, "text"), Some("body"));
+        assert_eq!(field(&text, "sr"), Some("rust"));
+        assert_eq!(field(&text, "sendreplies"), Some("true"));
+
+        let link = SubmissionBuilder::link("rust", "t", "https://rust-lang.org").form_data();
+        assert_eq!(field(&link, "kind"), Some("link"));
+        assert_eq!(field(&link, "url"), Some("https://rust-lang.org"));
+        assert_eq!(field(&link, "resubmit"), Some("true"));
+
+        let link = SubmissionBuilder::link("rust", "t", "https://rust-lang.org")
+            .resubmit(false)
+            .form_data();
+        assert_eq!(field(&link, "resubmit"), Some("false"));
+
+        let original: Fullname = "t3_abc".parse().ok().unwrap();
+        let crosspost = SubmissionBuilder::crosspost("rust", "t", &original).form_data();
+        assert_eq!(field(&crosspost, "kind"), Some("crosspost"));
+        assert_eq!(field(&crosspost, "crosspost_fullname"), Some("t3_abc"));
+    }
+
+    #[test]
+    fn form_data_names_media_kinds() {
+        let image = media(MediaKind::Image, "https://i/a.png");
+        let video = media(MediaKind::Video, "https://v/a.mp4");
+
+        let data = SubmissionBuilder::image("rust", "t", &image).form_data();
+        assert_eq!(field(&data, "kind"), Some("image"));
+        assert_eq!(field(&data, "url"), Some("https://i/a.png"));
+
+        let data = SubmissionBuilder::video("rust", "t", &video, &image, false).form_data();
+        assert_eq!(field(&data, "kind"), Some("video"));
+        assert_eq!(field(&data, "url"), Some("https://v/a.mp4"));
+        assert_eq!(field(&data, "video_poster_url"), Some("https://i/a.png"));
+
+        let data = SubmissionBuilder::video("rust", "t", &video, &image, true).form_data();
+        assert_eq!(field(&data, "kind"), Some("videogif"));
+    }
+
+    #[test]
+    fn flair_is_only_sent_when_set() {
+        let data = SubmissionBuilder::text("rust", "t", "").form_data();
+        assert_eq!(field(&data, "flair_id"), None);
+
+        let data = SubmissionBuilder::text("rust", "t", "")
+            .flair(Some("f1"), Some("news"))
+            .form_data();
+        assert_eq!(field(&data, "flair_id"), Some("f1"));
+        assert_eq!(field(&data, "flair_text"), Some("news"));
+    }
+
+    #[test]
+    fn json_body_carries_polls_and_galleries() {
+        let poll = SubmissionBuilder::poll("rust", "t", "pick", options(2), 3).json_body();
+        assert_eq!(poll["options"], json!(["0", "1"]));
+        assert_eq!(poll["duration"], json!(3));
+        assert_eq!(poll["text"], json!("pick"));
+
+        let items = vec![GalleryItem::new("m1", Some("one"), None), GalleryItem::new("m2", None, None)];
+        let gallery = SubmissionBuilder::gallery("rust", "t", items).json_body();
+        assert_eq!(gallery["items"][0]["media_id"], json!("m1"));
+        assert_eq!(gallery["items"][0]["caption"], json!("one"));
+        assert_eq!(gallery["show_error_list"], json!(true));
+    }
+
+    #[test]
+    fn submitted_fullname_reads_name_or_id() {
+        let from_name = submitted_fullname(json!({"name": "t3_abc", "id": "abc"}));
+        assert_eq!(from_name.ok().map(|f| f.to_string()), Some("t3_abc".to_string()));
+
+        let from_id = submitted_fullname(json!({"id": "t3_def"}));
+        assert_eq!(from_id.ok().map(|f| f.to_string()), Some("t3_def".to_string()));
+    }
+
+    #[test]
+    fn responses_without_a_fullname_are_bad_json() {
+        let server = MockServer::start(|_| (200, json!({"json": {"errors": [], "data": {"url": "x"}}})));
+        let mut session = server.session();
+
+        let result = test_util::runtime().block_on(SubmissionBuilder::text("rust", "t", "").submit(&mut session));
+
+        match result {
+            Err(RawrCoreError::BadJSON(data)) => assert_eq!(data.body, r#"{"url":"x"}"#),
+            _ => panic!("expected BadJSON"),
+        }
+        assert_eq!(server.requests()[0].form("kind").as_deref(), Some("self"));
+    }
+}
@@ -0,0 +1,144 @@
+use serde::Deserialize;
+
+use super::post_form;
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    models::{
+        things::{Subreddit, Thing},
+        Listing,
+    },
+    pagination::ListingPaginator,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const MAX_AUTOCOMPLETE_RESULTS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubredditCategory {
+    Popular,
+    New,
+    // the subreddits logged out users are subscribed to
+    Default,
+}
+impl SubredditCategory {
+    fn path(&self) -> &'static str {
+        match self {
+            SubredditCategory::Popular => "/subreddits/popular",
+            SubredditCategory::New => "/subreddits/new",
+            SubredditCategory::Default => "/subreddits/default",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubredditSearchSort {
+    Relevance,
+    Activity,
+}
+impl SubredditSearchSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SubredditSearchSort::Relevance => "relevance",
+            SubredditSearchSort::Activity => "activity",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RedditNames {
+    names: Vec<String>,
+}
+
+pub async fn about<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+) -> Result<Subreddit, RawrCoreError> {
+    let path = format!("/r/{}/about", subreddit);
+    let mut value = session
+        .request::<(), (), (), ()>(RequestMethod::Get, path.as_str(), None, None, None, None, None)
+        .await?;
+
+    util::from_json_value::<Subreddit>(value["data"].take())
+}
+
+// subreddits whose name or description match `query`, as t5 things
+pub fn search<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    query: &str,
+    sort: SubredditSearchSort,
+    limit: Option<usize>,
+    after: Option<String>,
+) -> ListingPaginator<'s, R, Thing> {
+    let params = vec![
+        ("q".to_string(), query.to_string()),
+        ("sort".to_string(), sort.as_str().to_string()),
+    ];
+
+    ListingPaginator::new(
+        session,
+        constants::SEARCH_SUBREDDITS_PATH.as_str(),
+        Some(params),
+        limit,
+        after,
+    )
+}
+
+// names starting with `query`, or only `query` itself when `exact` is set
+pub async fn search_names<R: RetryStrategy>(
+    session: &mut Session<R>,
+    query: &str,
+    exact: bool,
+    include_over_18: bool,
+) -> Result<Vec<String>, RawrCoreError> {
+    let data = [
+        ("query", query.to_string()),
+        ("exact", exact.to_string()),
+        ("include_over_18", include_over_18.to_string()),
+    ];
+
+    let value = post_form(session, constants::SEARCH_REDDIT_NAMES_PATH.as_str(), &data).await?;
+
+    Ok(util::from_json_value::<RedditNames>(value)?.names)
+}
+
+// at most ten suggestions for a partially typed name. user profiles are
+// returned as t2 things when `include_profiles` is set
+pub async fn autocomplete<R: RetryStrategy>(
+    session: &mut Session<R>,
+    query: &str,
+    include_over_18: bool,
+    include_profiles: bool,
+    limit: usize,
+) -> Result<Vec<Thing>, RawrCoreError> {
+    let params = [
+        ("query", query.to_string()),
+        ("include_over_18", include_over_18.to_string()),
+        ("include_profiles", include_profiles.to_string()),
+        ("limit", limit.min(MAX_AUTOCOMPLETE_RESULTS).to_string()),
+    ];
+
+    let value = session
+        .request::<(), (), (), [(&str, String); 4]>(
+            RequestMethod::Get,
+            constants::SUBREDDIT_AUTOCOMPLETE_PATH.as_str(),
+            None,
+            None,
+            None,
+            Some(&params),
+            None,
+        )
+        .await?;
+
+    Ok(util::from_json_value::<Listing<Thing>>(value)?.children)
+}
+
+pub fn listing<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    category: SubredditCategory,
+    limit: Option<usize>,
+    after: Option<String>,
+) -> ListingPaginator<'s, R, Thing> {
+    ListingPaginator::new(session, category.path(), None, limit, after)
+}
@@ -0,0 +1,536 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::post_form;
+use crate::{
+    errors::{BadJSONData, ConflictData, EditConflictData, RawrCoreError, RequestErrorData},
+    models::wiki::{WikiPage, WikiPageSettings, WikiPermLevel, WikiRevision},
+    pagination::ListingPaginator,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const DIFF_CONTEXT: usize = 3;
+// past this many changed lines the diff stops looking for the shortest
+// edit script and replaces the changed region wholesale
+const MAX_DIFF_EDITS: usize = 1000;
+const MAX_REASON_LENGTH: usize = 256;
+
+// the body of a 409 from wiki/edit, which carries the page as it is now
+#[derive(Deserialize)]
+struct EditConflictBody {
+    #[serde(default)]
+    newcontent: String,
+    newrevision: Option<String>,
+}
+
+fn edit_conflict(body: String, submitted: &str) -> Result<EditConflictData, RawrCoreError> {
+    match serde_json::from_str::<EditConflictBody>(body.as_str()) {
+        Ok(conflict) => Ok(EditConflictData {
+            submitted: submitted.to_string(),
+            current: conflict.newcontent,
+            current_revision: conflict.newrevision,
+        }),
+        Err(e) => Err(RawrCoreError::BadJSON(BadJSONData {
+            body,
+            original_error: e,
+        })),
+    }
+}
+
+fn validate_reason(reason: Option<&str>) -> Result<(), RawrCoreError> {
+    match reason {
+        Some(reason) if reason.chars().count() > MAX_REASON_LENGTH => Err(
+            RawrCoreError::InvalidInvocation("wiki edit reasons must be at most 256 characters"),
+        ),
+        _ => Ok(()),
+    }
+}
+
+async fn get<R: RetryStrategy>(
+    session: &mut Session<R>,
+    path: &str,
+    params: Option<&[(&str, String)]>,
+) -> Result<Value, RawrCoreError> {
+    session
+        .request::<(), (), (), [(&str, String)]>(RequestMethod::Get, path, None, None, None, params, None)
+        .await
+}
+
+// names of every page the account can see
+pub async fn pages<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+) -> Result<Vec<String>, RawrCoreError> {
+    let path = format!("/r/{}/wiki/pages", subreddit);
+    let mut value = get(session, path.as_str(), None).await?;
+
+    util::from_json_value::<Vec<String>>(value["data"].take())
+}
+
+// the latest version of `page`, or the one saved in `revision`
+pub async fn page<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page: &str,
+    revision: Option<&str>,
+) -> Result<WikiPage, RawrCoreError> {
+    let path = format!("/r/{}/wiki/{}", subreddit, page);
+    let params: Vec<(&str, String)> = match revision {
+        Some(revision) => vec![("v", revision.to_string())],
+        None => vec![],
+    };
+
+    let value = get(session, path.as_str(), Some(&params)).await?;
+
+    WikiPage::from_value(value)
+}
+
+// `previous` is the revision the new content was based on. when someone
+// else saved the page since then, a `Conflict` carrying both versions is
+// returned instead of overwriting their edit
+pub async fn edit<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page_name: &str,
+    content: &str,
+    previous: Option<&str>,
+    reason: Option<&str>,
+) -> Result<(), RawrCoreError> {
+    validate_reason(reason)?;
+
+    let path = format!("/r/{}/api/wiki/edit", subreddit);
+    let mut data = vec![
+        ("page", page_name.to_string()),
+        ("content", content.to_string()),
+    ];
+
+    if let Some(previous) = previous {
+        data.push(("previous", previous.to_string()));
+    }
+
+    if let Some(reason) = reason {
+        data.push(("reason", reason.to_string()));
+    }
+
+    let response = match post_form(session, path.as_str(), &data).await {
+        Err(RawrCoreError::Conflict(ConflictData {
+            response: Some(response),
+            ..
+        })) => response,
+        other => return other.map(|_| ()),
+    };
+
+    let body = match response.text().await {
+        Ok(body) => body,
+        Err(e) => {
+            return Err(RawrCoreError::RequestError(RequestErrorData {
+                original_error: e,
+                request: None,
+            }))
+        }
+    };
+
+    Err(RawrCoreError::Conflict(ConflictData {
+        response: None,
+        edit: Some(edit_conflict(body, content)?),
+    }))
+}
+
+// the revisions of `page`, newest first. every page of the subreddit is
+// included when no page is given
+pub fn revisions<'s, R: RetryStrategy>(
+    session: &'s mut Session<R>,
+    subreddit: &str,
+    page: Option<&str>,
+    limit: Option<usize>,
+    after: Option<String>,
+) -> ListingPaginator<'s, R, WikiRevision> {
+    let path = match page {
+        Some(page) => format!("/r/{}/wiki/revisions/{}", subreddit, page),
+        None => format!("/r/{}/wiki/revisions", subreddit),
+    };
+
+    ListingPaginator::new(session, path.as_str(), None, limit, after)
+}
+
+pub async fn revert<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page: &str,
+    revision: &str,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/r/{}/api/wiki/revert", subreddit);
+    let data = [("page", page.to_string()), ("revision", revision.to_string())];
+
+    post_form(session, path.as_str(), &data).await?;
+
+    Ok(())
+}
+
+pub async fn settings<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page: &str,
+) -> Result<WikiPageSettings, RawrCoreError> {
+    let path = format!("/r/{}/wiki/settings/{}", subreddit, page);
+    let value = get(session, path.as_str(), None).await?;
+
+    WikiPageSettings::from_value(value)
+}
+
+// unlisted pages are hidden from the page list but can still be opened
+pub async fn update_settings<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page: &str,
+    permlevel: WikiPermLevel,
+    listed: bool,
+) -> Result<WikiPageSettings, RawrCoreError> {
+    let path = format!("/r/{}/wiki/settings/{}", subreddit, page);
+    let data = [
+        ("permlevel", permlevel.as_level().to_string()),
+        ("listed", listed.to_string()),
+    ];
+
+    let value = post_form(session, path.as_str(), &data).await?;
+
+    WikiPageSettings::from_value(value)
+}
+
+async fn allow_editor<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page: &str,
+    username: &str,
+    action: &str,
+) -> Result<(), RawrCoreError> {
+    let path = format!("/r/{}/api/wiki/alloweditor/{}", subreddit, action);
+    let data = [("page", page.to_string()), ("username", username.to_string())];
+
+    post_form(session, path.as_str(), &data).await?;
+
+    Ok(())
+}
+
+// editors can change the page regardless of its permlevel
+pub async fn add_editor<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page: &str,
+    username: &str,
+) -> Result<(), RawrCoreError> {
+    allow_editor(session, subreddit, page, username, "add").await
+}
+
+pub async fn remove_editor<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page: &str,
+    username: &str,
+) -> Result<(), RawrCoreError> {
+    allow_editor(session, subreddit, page, username, "del").await
+}
+
+// a unified diff of `page` between two revisions. the latest version is
+// used when `to` is not given
+pub async fn diff<R: RetryStrategy>(
+    session: &mut Session<R>,
+    subreddit: &str,
+    page_name: &str,
+    from: &str,
+    to: Option<&str>,
+) -> Result<String, RawrCoreError> {
+    let old = page(session, subreddit, page_name, Some(from)).await?;
+    let new = page(session, subreddit, page_name, to).await?;
+
+    Ok(unified_diff(
+        old.content_md.as_str(),
+        new.content_md.as_str(),
+        from,
+        to.or(new.revision_id.as_deref()).unwrap_or("latest"),
+    ))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+impl DiffOp {
+    fn prefix(&self) -> char {
+        match self {
+            DiffOp::Equal => ' ',
+            DiffOp::Delete => '-',
+            DiffOp::Insert => '+',
+        }
+    }
+}
+
+// the shortest edit script of the middle lines (Myers, 1986). only the
+// part of each round's frontier that the backtrack reads is kept, so the
+// trace stays within O(MAX_DIFF_EDITS^2)
+fn shortest_edit<'a>(old: &[&'a str], new: &[&'a str]) -> Option<Vec<(DiffOp, &'a str)>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let limit = (n + m).min(MAX_DIFF_EDITS as isize);
+    let offset = limit + 1;
+
+    // frontier[offset + k] is the furthest x reached on diagonal k = x - y
+    let mut frontier = vec![0isize; (2 * limit + 3) as usize];
+    let mut trace: Vec<Vec<isize>> = vec![];
+    let mut edits = None;
+
+    'search: for d in 0..=limit {
+        trace.push(frontier[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+
+        for k in (-d..=d).step_by(2) {
+            let from_above = k == -d
+                || (k != d && frontier[(offset + k - 1) as usize] < frontier[(offset + k + 1) as usize]);
+            let mut x = match from_above {
+                true => frontier[(offset + k + 1) as usize],
+                false => frontier[(offset + k - 1) as usize] + 1,
+            };
+            let mut y = x - k;
+
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            frontier[(offset + k) as usize] = x;
+
+            if x >= n && y >= m {
+                edits = Some(d);
+                break 'search;
+            }
+        }
+    }
+
+    let mut ops = vec![];
+    let (mut x, mut y) = (n, m);
+
+    for d in (0..=edits?).rev() {
+        // trace[d] covers diagonals -d - 1 to d + 1
+        let previous = |k: isize| trace[d as usize][(k + d + 1) as usize];
+        let k = x - y;
+        let prev_k = match k == -d || (k != d && previous(k - 1) < previous(k + 1)) {
+            true => k + 1,
+            false => k - 1,
+        };
+        let prev_x = previous(prev_k);
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            ops.push((DiffOp::Equal, old[(x - 1) as usize]));
+            x -= 1;
+            y -= 1;
+        }
+
+        if d > 0 {
+            match x == prev_x {
+                true => ops.push((DiffOp::Insert, new[(y - 1) as usize])),
+                false => ops.push((DiffOp::Delete, old[(x - 1) as usize])),
+            }
+
+            x = prev_x;
+            y = prev_y;
+        }
+    }
+
+    ops.reverse();
+
+    Some(ops)
+}
+
+// line operations turning `old` into `new`. lines shared at the start and
+// end are matched up front so only the changed region is searched
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffOp, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(o, n)| o == n).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(o, n)| o == n)
+        .count();
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(DiffOp, &str)> = old[..prefix].iter().map(|line| (DiffOp::Equal, *line)).collect();
+
+    match shortest_edit(old_middle, new_middle) {
+        Some(middle) => ops.extend(middle),
+        None => {
+            ops.extend(old_middle.iter().map(|line| (DiffOp::Delete, *line)));
+            ops.extend(new_middle.iter().map(|line| (DiffOp::Insert, *line)));
+        }
+    }
+
+    ops.extend(old[old.len() - suffix..].iter().map(|line| (DiffOp::Equal, *line)));
+
+    ops
+}
+
+fn hunk_range(start: usize, count: usize) -> String {
+    // empty ranges point at the line before them
+    let start = if count == 0 { start } else { start + 1 };
+
+    format!("{},{}", start, count)
+}
+
+// a unified diff with three lines of context, or an empty string when both
+// versions are identical
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != DiffOp::Equal)
+        .map(|(index, _)| index)
+        .collect();
+
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // (start, end) op ranges, merged when their context overlaps
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for change in changes {
+        let start = change.saturating_sub(DIFF_CONTEXT);
+        let end = (change + DIFF_CONTEXT + 1).min(ops.len());
+
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", old_label, new_label);
+
+    for (start, end) in hunks {
+        let count = |skip: DiffOp, range: &[(DiffOp, &str)]| range.iter().filter(|(op, _)| *op != skip).count();
+
+        diff.push_str(
+            format!(
+                "@@ -{} +{} @@\n",
+                hunk_range(count(DiffOp::Insert, &ops[..start]), count(DiffOp::Insert, &ops[start..end])),
+                hunk_range(count(DiffOp::Delete, &ops[..start]), count(DiffOp::Delete, &ops[start..end])),
+            )
+            .as_str(),
+        );
+
+        for (op, line) in &ops[start..end] {
+            diff.push(op.prefix());
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<&str> {
+        text.lines().collect()
+    }
+
+    // applies the ops to check they really turn `old` into `new`
+    fn apply<'a>(old: &[&'a str], ops: &[(DiffOp, &'a str)]) -> Vec<&'a str> {
+        let mut old = old.iter();
+        let mut result = vec![];
+
+        for (op, line) in ops {
+            match op {
+                DiffOp::Equal => {
+                    assert_eq!(old.next(), Some(line));
+                    result.push(*line);
+                }
+                DiffOp::Delete => assert_eq!(old.next(), Some(line)),
+                DiffOp::Insert => result.push(*line),
+            }
+        }
+
+        assert_eq!(old.next(), None);
+
+        result
+    }
+
+    #[test]
+    fn identical_text_has_no_diff() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "1", "2"), "");
+    }
+
+    #[test]
+    fn single_change_gets_one_hunk_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+
+        assert_eq!(
+            unified_diff(old, new, "old", "new"),
+            "--- old\n+++ new\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn distant_changes_get_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+        let new = old.replace("2\n", "two\n").replacen("19\n", "nineteen\n", 1);
+
+        let diff = unified_diff(old.as_str(), new.as_str(), "old", "new");
+
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("-19\n+nineteen\n"));
+    }
+
+    #[test]
+    fn empty_sides_use_zero_length_ranges() {
+        assert_eq!(unified_diff("", "a\n", "old", "new"), "--- old\n+++ new\n@@ -0,0 +1,1 @@\n+a\n");
+        assert_eq!(unified_diff("a\n", "", "old", "new"), "--- old\n+++ new\n@@ -1,1 +0,0 @@\n-a\n");
+    }
+
+    #[test]
+    fn diff_is_minimal() {
+        let old = lines("a\nb\nc\na\nb\nb\na");
+        let new = lines("c\nb\na\nb\na\nc");
+        let ops = diff_lines(&old, &new);
+        let edits = ops.iter().filter(|(op, _)| *op != DiffOp::Equal).count();
+
+        assert_eq!(apply(&old, &ops), new);
+        // the classic example from Myers' paper has an edit distance of 5
+        assert_eq!(edits, 5);
+    }
+
+    #[test]
+    fn large_rewrites_fall_back_to_replacing_the_region() {
+        let old: Vec<String> = (0..MAX_DIFF_EDITS).map(|i| format!("old {}", i)).collect();
+        let new: Vec<String> = (0..MAX_DIFF_EDITS).map(|i| format!("new {}", i)).collect();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+
+        let ops = diff_lines(&old, &new);
+
+        assert_eq!(apply(&old, &ops), new);
+        assert_eq!(ops.len(), old.len() + new.len());
+    }
+
+    #[test]
+    fn edit_conflicts_carry_the_current_page() {
+        let body = r#"{"message": "Conflict", "reason": "EDIT_CONFLICT", "newcontent": "theirs", "newrevision": "abc"}"#;
+
+        match edit_conflict(body.to_string(), "mine") {
+            Ok(conflict) => {
+                assert_eq!(conflict.submitted, "mine");
+                assert_eq!(conflict.current, "theirs");
+                assert_eq!(conflict.current_revision.as_deref(), Some("abc"));
+            }
+            Err(_) => panic!("expected the conflict body to parse"),
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod authenticators;
-pub mod authorizers;
\ No newline at end of file
+pub mod authorizers;
+pub mod scopes;
\ No newline at end of file
@@ -1,34 +1,28 @@
-use std::vec;
-
-use reqwest::{StatusCode, Response, Url};
-use serde::Serialize;
+use async_trait::async_trait;
+use reqwest::{Method, StatusCode, Response, Url};
 
 use crate::{requestor::Requestor, errors::{RawrCoreError, ResponseErrorData}, constants};
 
-use super::authorizers::BaseAuthorizer;
-
-pub trait Authenticator {    
-    fn new(requestor: Requestor, client_id: &str, client_secret: &str, redirect_uri: Option<&str>) -> Self;
-
+#[async_trait]
+pub trait Authenticator: Send + Sync {
     fn auth(&self) -> (String, String);
 
-    fn post<T>(&self, url: &str, success_status: Option<StatusCode>, data: T) -> Result<Response, RawrCoreError> where T: Serialize {
-        if success_status.is_none() {
-            success_status = Some(StatusCode::OK)
-        }
+    async fn post(&self, url: &str, success_status: Option<StatusCode>, data: &[(&str, &str)]) -> Result<Response, RawrCoreError> {
+        let success_status = match success_status {
+            Some(status) => status,
+            None => StatusCode::OK,
+        };
 
         let requestor = self.get_requestor();
-        
-        // TODO: fixme once requestor is implemented
-        let response = requestor.request();
+        let response = requestor.request(Method::POST, url, data, self.auth(), None).await?;
 
-        if response.status().ne(success_status.unwrap()) {
+        if response.status().ne(&success_status) {
             return Err(RawrCoreError::ResponseError(ResponseErrorData {
                 response
             }))
         }
 
-        response
+        Ok(response)
     }
 
     fn authorize_url(
@@ -38,7 +32,7 @@ pub trait Authenticator {
         state: &str,
         implicit: bool
     ) -> Result<String, RawrCoreError> {
-        if self.redirect_url.is_none() {
+        if self.get_redirect_uri().is_none() {
             return Err(RawrCoreError::InvalidInvocation("redirect_uri not provided"))
         }
 
@@ -46,107 +40,116 @@ pub trait Authenticator {
             return Err(RawrCoreError::InvalidInvocation("Only UntrustedAuthenticator can use the implicit grant flow"))
         }
 
-        if implicit && duration.eq("temporary") {
+        if implicit && !duration.eq("temporary") {
             return Err(RawrCoreError::InvalidInvocation("The implicit grant flow only supports temporary access tokens"))
         } 
 
         let requestor = self.get_requestor();
-        let url_string =  requestor.reddit_url + constants::AUTHORIZATION_PATH;
+        let url_string = requestor.reddit_url.clone() + constants::AUTHORIZATION_PATH.as_str();
+        let scope = scopes.join(" ");
         let params = [
             ("client_id", self.get_client_id()),
             ("duration", duration),
-            ("redirect_uri", self.get_redirect_uri()),
+            ("redirect_uri", self.get_redirect_uri().unwrap()),
             ("response_type", match implicit {
                 true => "token",
                 false => "code"
             }),
-            ("scope", scopes.join(" ")),
+            ("scope", scope.as_str()),
             ("state", state)
         ];
 
         match Url::parse_with_params(url_string.as_str(), params) {
-            Ok(url) => url.as_str(),
-            Err(e) => Err(RawrCoreError::InvalidInvocation("Error creating url in authorize_url"))
+            Ok(url) => Ok(url.to_string()),
+            Err(_) => Err(RawrCoreError::InvalidInvocation("Error creating url in authorize_url"))
         }
     }
 
-    fn revoke_token(&self, token: &str, token_type: Option<&str>) {
-        let data = vec![("token", token)];
-        if token_type.is_some() {
+    async fn revoke_token(&self, token: &str, token_type: Option<&str>) -> Result<(), RawrCoreError> {
+        let mut data = vec![("token", token)];
+        if let Some(token_type) = token_type {
             data.push(("token_type_hint", token_type))
         }
 
         let requestor = self.get_requestor();
-        let url = requestor.reddit_url + constants::REVOKE_TOKEN_PATH;
-        self.post(url.as_str(), None, data);
+        let url = requestor.reddit_url.clone() + constants::REVOKE_TOKEN_PATH.as_str();
+        self.post(url.as_str(), None, &data).await?;
+
+        Ok(())
     }
 
     fn get_redirect_uri(&self) -> Option<&str>;
     fn get_requestor(&self) -> &Requestor;
     fn get_client_id(&self) -> &str;
     fn get_client_secret(&self) -> &str;
-    fn is_untrusted() -> bool;
+    fn is_untrusted(&self) -> bool;
 }
 
-pub struct TrustedAuthenticator<'a> {
+pub struct TrustedAuthenticator {
     requestor: Requestor,
-    client_id: &'a str,
-    client_secret: &'a str,
-    redirect_uri: Option<&'a str>,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: Option<String>,
 }
-impl <'a> TrustedAuthenticator<'a>  {
-    fn new(requestor: Requestor, client_id: &str, client_secret: &str, redirect_uri: Option<&str>) -> Self {
+impl TrustedAuthenticator {
+    pub fn new(requestor: Requestor, client_id: &str, client_secret: &str, redirect_uri: Option<&str>) -> Self {
         Self {
             requestor,
-            client_id,
-            client_secret,
-            redirect_uri
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            redirect_uri: redirect_uri.map(|uri| uri.to_string())
         }
     }
 }
-impl <'a> Authenticator for TrustedAuthenticator<'a> {
+#[async_trait]
+impl Authenticator for TrustedAuthenticator {
     fn get_redirect_uri(&self) -> Option<&str> {
-        self.redirect_uri
+        self.redirect_uri.as_deref()
     }
 
     fn get_requestor(&self) -> &Requestor {
         &self.requestor
     }
 
-    fn get_client_id(&self) {
-        self.client_id
+    fn get_client_id(&self) -> &str {
+        self.client_id.as_str()
     }
 
     fn get_client_secret(&self) -> &str {
-        self.client_secret
+        self.client_secret.as_str()
     }
 
     fn auth(&self) -> (String, String) {
-        (self.client_id.to_string(), self.client_secret.to_string())
+        (self.client_id.clone(), self.client_secret.clone())
+    }
+
+    fn is_untrusted(&self) -> bool {
+        false
     }
 }
 
-pub struct UntrustedAuthenticator<'a> {
+pub struct UntrustedAuthenticator {
     requestor: Requestor,
-    client_id: &'a str,
-    redirect_uri: Option<&'a str>
+    client_id: String,
+    redirect_uri: Option<String>
 }
-impl <'a> UntrustedAuthenticator<'a> {
-    fn new(requestor: Requestor, client_id: &str, redirect_uri: Option<&str>) -> Self {
+impl UntrustedAuthenticator {
+    pub fn new(requestor: Requestor, client_id: &str, redirect_uri: Option<&str>) -> Self {
         Self {
             requestor,
-            client_id,
-            redirect_uri
+            client_id: client_id.to_string(),
+            redirect_uri: redirect_uri.map(|uri| uri.to_string())
         }
     }
 }
-impl <'a> Authenticator for UntrustedAuthenticator<'a> {
+#[async_trait]
+impl Authenticator for UntrustedAuthenticator {
     fn get_requestor(&self) -> &Requestor {
         &self.requestor
     }
 
     fn get_client_id(&self) -> &str {
-        self.client_id
+        self.client_id.as_str()
     }
 
     fn get_client_secret(&self) -> &str {
@@ -154,7 +157,14 @@ impl <'a> Authenticator for UntrustedAuthenticator<'a> {
     }
 
     fn get_redirect_uri(&self) -> Option<&str> {
-       self.redirect_uri 
+       self.redirect_uri.as_deref()
     }
-}
 
+    fn auth(&self) -> (String, String) {
+        (self.client_id.clone(), "".to_string())
+    }
+
+    fn is_untrusted(&self) -> bool {
+        true
+    }
+}
@@ -1,17 +1,54 @@
+use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
-use serde::Serialize;
 use std::collections::HashSet;
 
 use crate::{
     constants,
-    errors::{OAuthErrorData, RawrCoreError, ResponseErrorData},
+    errors::{BadJSONData, OAuthErrorData, RawrCoreError, RequestErrorData},
     models::RequestTokenResponse,
+    requestor::Requestor,
 };
 
 use super::authenticators::Authenticator;
 
+pub type RefreshCallback = Box<dyn Fn(&CodeAuthorizer) + Send + Sync>;
+pub type TwoFactorCallback = Box<dyn Fn() -> String + Send + Sync>;
+
+#[async_trait]
+pub trait Authorizer: Send + Sync {
+    async fn refresh(&mut self) -> Result<(), RawrCoreError>;
+
+    fn access_token(&self) -> Result<&str, RawrCoreError> {
+        match &self.get_base().access_token {
+            Some(token) => Ok(token.as_str()),
+            None => Err(RawrCoreError::InvalidInvocation(
+                "no access token available; call refresh first",
+            )),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.get_base().is_valid()
+    }
+
+    async fn revoke(&mut self) -> Result<(), RawrCoreError> {
+        self.get_base_mut().revoke().await
+    }
+
+    fn scopes(&self) -> Option<&HashSet<String>> {
+        self.get_base().scopes()
+    }
+
+    fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.get_base().expires_at()
+    }
+
+    fn get_base(&self) -> &BaseAuthorizer;
+    fn get_base_mut(&mut self) -> &mut BaseAuthorizer;
+}
+
 pub struct BaseAuthorizer {
-    authenticator: Authenticator,
+    authenticator: Box<dyn Authenticator>,
     expiration_timestamp: Option<DateTime<Utc>>,
     access_token: Option<String>,
     refresh_token: Option<String>,
@@ -19,7 +56,7 @@ pub struct BaseAuthorizer {
     authenticator_is_trusted: bool,
 }
 impl BaseAuthorizer {
-    pub fn new(authenticator: impl Authenticator, is_trusted: bool) -> Self {
+    pub fn new(authenticator: Box<dyn Authenticator>, is_trusted: bool) -> Self {
         Self {
             authenticator,
             expiration_timestamp: None,
@@ -30,35 +67,61 @@ impl BaseAuthorizer {
         }
     }
 
-    fn clear_access_token(&self) {
+    pub fn get_requestor(&self) -> &Requestor {
+        self.authenticator.get_requestor()
+    }
+
+    pub fn scopes(&self) -> Option<&HashSet<String>> {
+        self.scopes.as_ref()
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expiration_timestamp
+    }
+
+    fn clear_access_token(&mut self) {
         self.expiration_timestamp = None;
         self.access_token = None;
         self.scopes = None;
     }
 
-    async fn request_token<T>(&mut self, data: T) -> Result<(), RawrCoreError>
-    where
-        T: Serialize,
-    {
-        let url =
-            self.authenticator.get_requestor().reddit_url + constants::ACCESS_TOKEN_PATH.as_str();
+    async fn request_token(&mut self, data: &[(&str, &str)]) -> Result<(), RawrCoreError> {
+        let url = format!(
+            "{}{}",
+            self.authenticator.get_requestor().reddit_url,
+            constants::ACCESS_TOKEN_PATH.as_str()
+        );
         let pre_request_time = Utc::now();
-        let response = self.authenticator.post(url.as_str(), None, data);
+        let response = self.authenticator.post(url.as_str(), None, data).await;
 
         if response.is_err() {
             return Err(response.err().unwrap());
         }
 
-        let res = response.unwrap();
-        let payload = res
-            .json::<RequestTokenResponse>()
-            .await
-            .expect("Could not parse request_token response to JSON.");
+        let res = response.ok().unwrap();
+        let body = match res.text().await {
+            Ok(body) => body,
+            Err(e) => {
+                return Err(RawrCoreError::RequestError(RequestErrorData {
+                    original_error: e,
+                    request: None,
+                }))
+            }
+        };
+
+        let payload = match serde_json::from_str::<RequestTokenResponse>(body.as_str()) {
+            Ok(payload) => payload,
+            Err(e) => {
+                return Err(RawrCoreError::BadJSON(BadJSONData {
+                    body,
+                    original_error: e,
+                }))
+            }
+        };
 
-        if payload.error.is_some() {
+        if let Some(error) = payload.error {
             return Err(RawrCoreError::OAuthError(OAuthErrorData {
-                response: &response,
-                error: payload.error.unwrap(),
+                error,
                 description: payload.error_description,
             }));
         }
@@ -70,15 +133,12 @@ impl BaseAuthorizer {
 
         self.access_token = Some(payload.access_token);
         self.refresh_token = payload.refresh_token;
-        self.scopes = Some(HashSet::new::<String>());
-
-        for scope in payload.scope.split(" ") {
-            self.scopes.get_or_insert(scope)
-        }
+        self.scopes = Some(payload.scope.split(" ").map(|s| s.to_string()).collect());
 
         Ok(())
     }
 
+    #[allow(dead_code)]
     fn validate_authenticator(&self) -> Result<(), RawrCoreError> {
         // TODO: implement? maybe?
         Ok(())
@@ -90,7 +150,7 @@ impl BaseAuthorizer {
             && Utc::now().cmp(&self.expiration_timestamp.unwrap()).is_lt()
     }
 
-    fn revoke(&self) -> Result<(), RawrCoreError> {
+    async fn revoke(&mut self) -> Result<(), RawrCoreError> {
         if self.access_token.is_none() {
             return Err(RawrCoreError::InvalidInvocation(
                 "no token available to revoke",
@@ -98,23 +158,24 @@ impl BaseAuthorizer {
         }
 
         self.authenticator
-            .revoke_token(self.access_token.unwrap().as_str(), Some("access_token"));
+            .revoke_token(self.access_token.as_ref().unwrap().as_str(), Some("access_token"))
+            .await?;
         self.clear_access_token();
 
         Ok(())
     }
 }
 
-pub struct Authorizer {
+pub struct CodeAuthorizer {
     base: BaseAuthorizer,
-    post_refresh_callback: Option<Fn(Authorizer)>,
-    pre_refresh_callback: Option<Fn(Authorizer)>,
+    post_refresh_callback: Option<RefreshCallback>,
+    pre_refresh_callback: Option<RefreshCallback>,
 }
-impl Authorizer {
+impl CodeAuthorizer {
     pub fn new(
         base: BaseAuthorizer,
-        post_refresh_callback: Option<Fn(Authorizer)>,
-        pre_refresh_callback: Option<Fn(Authorizer)>,
+        post_refresh_callback: Option<RefreshCallback>,
+        pre_refresh_callback: Option<RefreshCallback>,
     ) -> Self {
         Self {
             base,
@@ -123,56 +184,74 @@ impl Authorizer {
         }
     }
 
-    pub async fn authorize(&self, code: &str) -> Result<(), RawrCoreError> {
-        if self.base.authenticator.get_redirect_uri().is_none() {
-            return Err(RawrCoreError::InvalidInvocation(
-                "redirect URI not provided",
-            ));
-        }
+    pub async fn authorize(&mut self, code: &str) -> Result<(), RawrCoreError> {
+        let redirect_uri = match self.base.authenticator.get_redirect_uri() {
+            Some(uri) => uri.to_string(),
+            None => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "redirect URI not provided",
+                ))
+            }
+        };
 
-        self.base.request_token([
+        self.base.request_token(&[
             ("code", code),
             ("grant_type", "authorization_code"),
-            ("redirect_uri", self.base.authenticator.get_redirect_uri()?),
+            ("redirect_uri", redirect_uri.as_str()),
         ]).await?;
 
         Ok(())
     }
 
-    pub async fn refresh(&self) -> Result<(), RawrCoreError> {
-        if self.pre_refresh_callback.is_some() {
-            self.pre_refresh_callback.unwrap()(self);
+    pub async fn revoke_access_token(&mut self) -> Result<(), RawrCoreError> {
+        self.base.revoke().await
+    }
+}
+#[async_trait]
+impl Authorizer for CodeAuthorizer {
+    async fn refresh(&mut self) -> Result<(), RawrCoreError> {
+        if let Some(callback) = &self.pre_refresh_callback {
+            callback(self);
         }
 
-        if self.base.refresh_token.is_none() {
-            return Err(RawrCoreError::InvalidInvocation("refresh token not provided"))
-        }
+        let refresh_token = match &self.base.refresh_token {
+            Some(token) => token.clone(),
+            None => return Err(RawrCoreError::InvalidInvocation("refresh token not provided")),
+        };
 
         self.base.request_token(
-            [
+            &[
                 ("grant_type", "refresh_token"),
-                ("refresh_token", self.base.refresh_token.unwrap().as_str())
+                ("refresh_token", refresh_token.as_str())
             ]
         ).await?;
 
-        if self.post_refresh_callback.is_some() {
-            self.post_refresh_callback.unwrap()(self);
+        if let Some(callback) = &self.post_refresh_callback {
+            callback(self);
         }
 
         Ok(())
     }
 
-    pub fn revoke(&mut self, only_access: bool) -> Result<(), RawrCoreError> {
-        if only_access || self.base.refresh_token.is_none() {
-            return self.base.revoke();
+    async fn revoke(&mut self) -> Result<(), RawrCoreError> {
+        if self.base.refresh_token.is_none() {
+            return self.base.revoke().await;
         }
 
-        self.base.authenticator.revoke_token(self.base.refresh_token.unwrap().as_str(), Some("refresh_token"))?;
-        self.base.clear_access_token()?;
+        self.base.authenticator.revoke_token(self.base.refresh_token.as_ref().unwrap().as_str(), Some("refresh_token")).await?;
+        self.base.clear_access_token();
         self.base.refresh_token = None;
 
         Ok(())
     }
+
+    fn get_base(&self) -> &BaseAuthorizer {
+        &self.base
+    }
+
+    fn get_base_mut(&mut self) -> &mut BaseAuthorizer {
+        &mut self.base
+    }
 }
 
 pub struct DeviceIdAuthorizer {
@@ -188,81 +267,218 @@ impl DeviceIdAuthorizer {
             scopes
         }
     }
-
-    pub async fn refresh(&self) -> Result<(), RawrCoreError> {
-        let data: Vec<(&str, &str)> = vec![];
-
-        if self.scopes.is_some() {
-            let scopes = self.scopes.unwrap();
-            data.push(("scope", scopes.join(" ").as_str()));
+}
+#[async_trait]
+impl Authorizer for DeviceIdAuthorizer {
+    async fn refresh(&mut self) -> Result<(), RawrCoreError> {
+        let scopes = self.scopes.as_ref().map(|s| s.join(" "));
+        let device_id = self.device_id.clone().unwrap_or("".to_string());
+        let mut data: Vec<(&str, &str)> = vec![];
+
+        if let Some(scopes) = &scopes {
+            data.push(("scope", scopes.as_str()));
         }
 
         let grant_type = "https://oauth.reddit.com/grants/installed_client";
         data.push(("grant_type", grant_type));
-        data.push(("device_id", self.device_id.unwrap_or("".to_string()).as_str()));
-        
-        self.base.request_token(data).await
+        data.push(("device_id", device_id.as_str()));
+
+        self.base.request_token(&data).await
+    }
+
+    fn get_base(&self) -> &BaseAuthorizer {
+        &self.base
+    }
+
+    fn get_base_mut(&mut self) -> &mut BaseAuthorizer {
+        &mut self.base
     }
 }
 
 pub struct ImplicitAuthorizer {
     base: BaseAuthorizer,
-    access_token: String,
-    expiration_timestamp: DateTime<Utc>,
-    scopes: HashSet<String>,
 }
 impl ImplicitAuthorizer {
-    fn new(base: BaseAuthorizer, access_token: String, expires_in: u64, scope: String) -> Self {
-        let expiration_timestamp = Utc::now().checked_add_signed(Duration::seconds(expires_in)).expect("Could not parse datetime");
-        let scopes = HashSet::<String>::new();
+    fn new(mut base: BaseAuthorizer, access_token: String, expires_in: i64, scope: String) -> Result<Self, RawrCoreError> {
+        let expiration_timestamp = match Duration::try_seconds(expires_in)
+            .and_then(|expires_in| Utc::now().checked_add_signed(expires_in))
+        {
+            Some(timestamp) => timestamp,
+            None => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "expires_in in redirect is out of range",
+                ))
+            }
+        };
+
+        base.expiration_timestamp = Some(expiration_timestamp);
+        base.access_token = Some(access_token);
+        base.scopes = Some(
+            scope
+                .split(' ')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect(),
+        );
+
+        Ok(Self { base })
+    }
 
-        scope.split(" ").for_each(|s| {
-            scopes.insert(s.to_string());
-        });
+    pub fn from_redirect(base: BaseAuthorizer, redirect: &str, state: &str) -> Result<Self, RawrCoreError> {
+        if base.authenticator_is_trusted {
+            return Err(RawrCoreError::InvalidInvocation(
+                "the implicit grant flow requires an UntrustedAuthenticator",
+            ));
+        }
 
-        Self {
+        // accept either the full redirect URL or just its fragment
+        let fragment = match redirect.split_once('#') {
+            Some((_, fragment)) => fragment,
+            None => redirect,
+        };
+
+        let mut access_token: Option<String> = None;
+        let mut redirect_state: Option<String> = None;
+        let mut token_type: Option<String> = None;
+        let mut expires_in: Option<String> = None;
+        let mut scope: Option<String> = None;
+        let mut error: Option<String> = None;
+
+        for (key, value) in url::form_urlencoded::parse(fragment.as_bytes()) {
+            match key.as_ref() {
+                "access_token" => access_token = Some(value.into_owned()),
+                "state" => redirect_state = Some(value.into_owned()),
+                "token_type" => token_type = Some(value.into_owned()),
+                "expires_in" => expires_in = Some(value.into_owned()),
+                "scope" => scope = Some(value.into_owned()),
+                "error" => error = Some(value.into_owned()),
+                _ => (),
+            }
+        }
+
+        if redirect_state.is_none() || redirect_state.unwrap() != state {
+            return Err(RawrCoreError::InvalidInvocation(
+                "state in redirect does not match the state sent to authorize_url",
+            ));
+        }
+
+        if error.is_some() {
+            return Err(RawrCoreError::InvalidInvocation(
+                "authorization was not granted by the user",
+            ));
+        }
+
+        if access_token.is_none() {
+            return Err(RawrCoreError::InvalidInvocation(
+                "access_token missing from redirect",
+            ));
+        }
+
+        match token_type {
+            Some(tt) if tt.eq_ignore_ascii_case("bearer") => (),
+            _ => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "token_type in redirect is not bearer",
+                ))
+            }
+        }
+
+        let expires_in = match expires_in {
+            Some(ei) => match ei.parse::<i64>() {
+                Ok(seconds) => seconds,
+                Err(_) => {
+                    return Err(RawrCoreError::InvalidInvocation(
+                        "expires_in in redirect is not a number",
+                    ))
+                }
+            },
+            None => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "expires_in missing from redirect",
+                ))
+            }
+        };
+
+        Self::new(
             base,
-            access_token,
-            scopes,
-            expiration_timestamp
+            access_token.unwrap(),
+            expires_in,
+            scope.unwrap_or("".to_string()),
+        )
+    }
+}
+#[async_trait]
+impl Authorizer for ImplicitAuthorizer {
+    async fn refresh(&mut self) -> Result<(), RawrCoreError> {
+        Err(RawrCoreError::ReauthorizationRequired(
+            "implicit grants have no refresh token; the user must authorize the application again",
+        ))
+    }
+
+    fn access_token(&self) -> Result<&str, RawrCoreError> {
+        if !self.is_valid() {
+            return Err(RawrCoreError::ReauthorizationRequired(
+                "implicit grant access token has expired; the user must authorize the application again",
+            ));
         }
+
+        Ok(self.base.access_token.as_ref().unwrap().as_str())
+    }
+
+    fn get_base(&self) -> &BaseAuthorizer {
+        &self.base
+    }
+
+    fn get_base_mut(&mut self) -> &mut BaseAuthorizer {
+        &mut self.base
     }
 }
 
-struct ReadOnlyAuthorizer {
-    authorizer: Authorizer,
+pub struct ReadOnlyAuthorizer {
+    base: BaseAuthorizer,
     scopes: Option<Vec<String>>,
 }
 impl ReadOnlyAuthorizer {
-    fn new(authorizer: Authorizer, scopes: Option<Vec<String>>) -> Self {
+    pub fn new(base: BaseAuthorizer, scopes: Option<Vec<String>>) -> Self {
         Self {
-            authorizer,
+            base,
             scopes
         }
     }
-
-    pub async fn refresh(&self) -> Result<(), RawrCoreError> {
-        let data: Vec<(&str, &str)> = vec![];
-
-        if self.scopes.is_some() {
-            data.push(("scope", self.scopes.unwrap().join(" ").as_str()));
+}
+#[async_trait]
+impl Authorizer for ReadOnlyAuthorizer {
+    async fn refresh(&mut self) -> Result<(), RawrCoreError> {
+        let scopes = self.scopes.as_ref().map(|s| s.join(" "));
+        let mut data: Vec<(&str, &str)> = vec![];
+
+        if let Some(scopes) = &scopes {
+            data.push(("scope", scopes.as_str()));
         }
 
         data.push(("grant_type", "client_credentials"));
 
-        self.request_token(data).await
+        self.base.request_token(&data).await
+    }
+
+    fn get_base(&self) -> &BaseAuthorizer {
+        &self.base
+    }
+
+    fn get_base_mut(&mut self) -> &mut BaseAuthorizer {
+        &mut self.base
     }
 }
 
-struct ScriptAuthorizer {
+pub struct ScriptAuthorizer {
     base: BaseAuthorizer,
     username: Option<String>,
     password: Option<String>,
-    two_factor_callback: Option<Fn() -> String>,
+    two_factor_callback: Option<TwoFactorCallback>,
     scopes: Option<Vec<String>>
 }
 impl ScriptAuthorizer {
-    pub fn new(base: BaseAuthorizer, username: Option<String>, password: Option<String>, two_factor_callback: Option<Fn() -> String>, scopes: Option<Vec<String>>) -> Self {
+    pub fn new(base: BaseAuthorizer, username: Option<String>, password: Option<String>, two_factor_callback: Option<TwoFactorCallback>, scopes: Option<Vec<String>>) -> Self {
         Self {
             base,
             username,
@@ -271,24 +487,171 @@ impl ScriptAuthorizer {
             scopes
         }
     }
+}
+#[async_trait]
+impl Authorizer for ScriptAuthorizer {
+    async fn refresh(&mut self) -> Result<(), RawrCoreError> {
+        let scopes = self.scopes.as_ref().map(|s| s.join(" "));
+        let code = self.two_factor_callback.as_ref().map(|tfc| tfc());
+        let username = self.username.clone().unwrap_or("".to_string());
+        let password = self.password.clone().unwrap_or("".to_string());
+        let mut data: Vec<(&str, &str)> = vec![
+            ("grant_type", "password"),
+            ("username", username.as_str()),
+            ("password", password.as_str()),
+        ];
+
+        if let Some(scopes) = &scopes {
+            data.push(("scope", scopes.as_str()));
+        }
+
+        if let Some(code) = &code {
+            if !code.is_empty() {
+                data.push(("otp", code.as_str()));
+            }
+        }
+
+        self.base.request_token(&data).await
+    }
 
-    pub async fn refresh(&self) -> Result<(), RawrCoreError> {
-        let data: Vec<(&str, &str)> = vec![];
+    fn get_base(&self) -> &BaseAuthorizer {
+        &self.base
+    }
 
-        if self.scopes.is_some() {
-            let scopes = self.scopes.unwrap().join(" ").as_str();
-            data.push(("scope", scopes));
+    fn get_base_mut(&mut self) -> &mut BaseAuthorizer {
+        &mut self.base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::authenticators::{TrustedAuthenticator, UntrustedAuthenticator};
+
+    fn requestor() -> Requestor {
+        Requestor::new("rawrcore tests", None, None, None, None).ok().unwrap()
+    }
+
+    fn untrusted_base() -> BaseAuthorizer {
+        let authenticator = UntrustedAuthenticator::new(requestor(), "client", Some("http://localhost/cb"));
+
+        BaseAuthorizer::new(Box::new(authenticator), false)
+    }
+
+    fn invalid_invocation(result: Result<ImplicitAuthorizer, RawrCoreError>) -> &'static str {
+        match result {
+            Err(RawrCoreError::InvalidInvocation(message)) => message,
+            Err(_) => panic!("expected InvalidInvocation"),
+            Ok(_) => panic!("expected an error"),
         }
+    }
 
-        if self.two_factor_callback.is_some() {
-            let tfc: Fn() -> String = self.two_factor_callback.unwrap();
+    #[test]
+    fn from_redirect_parses_fragment() {
+        let redirect = "http://localhost/cb#access_token=abc&token_type=bearer&state=xyz&expires_in=3600&scope=read+identity";
+        let authorizer = ImplicitAuthorizer::from_redirect(untrusted_base(), redirect, "xyz").ok().unwrap();
 
-            let code = tfc();
-            if !code.is_empty() {
-                data.push(("otp", code.as_str()));
+        assert_eq!(authorizer.access_token().ok(), Some("abc"));
+        assert!(authorizer.is_valid());
+
+        let scopes = authorizer.scopes().unwrap();
+        assert_eq!(scopes.len(), 2);
+        assert!(scopes.contains("read") && scopes.contains("identity"));
+    }
+
+    #[test]
+    fn from_redirect_accepts_bare_fragment() {
+        let fragment = "access_token=abc&token_type=bearer&state=xyz&expires_in=3600";
+
+        assert!(ImplicitAuthorizer::from_redirect(untrusted_base(), fragment, "xyz").is_ok());
+    }
+
+    #[test]
+    fn from_redirect_without_scope_has_no_empty_scope() {
+        let fragment = "access_token=abc&token_type=bearer&state=xyz&expires_in=3600";
+        let authorizer = ImplicitAuthorizer::from_redirect(untrusted_base(), fragment, "xyz").ok().unwrap();
+
+        assert!(authorizer.scopes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn from_redirect_rejects_error() {
+        let fragment = "error=access_denied&state=xyz";
+
+        assert_eq!(
+            invalid_invocation(ImplicitAuthorizer::from_redirect(untrusted_base(), fragment, "xyz")),
+            "authorization was not granted by the user"
+        );
+    }
+
+    #[test]
+    fn from_redirect_rejects_missing_access_token() {
+        let fragment = "token_type=bearer&state=xyz&expires_in=3600";
+
+        assert_eq!(
+            invalid_invocation(ImplicitAuthorizer::from_redirect(untrusted_base(), fragment, "xyz")),
+            "access_token missing from redirect"
+        );
+    }
+
+    #[test]
+    fn from_redirect_rejects_state_mismatch() {
+        let fragment = "access_token=abc&token_type=bearer&state=other&expires_in=3600";
+
+        assert_eq!(
+            invalid_invocation(ImplicitAuthorizer::from_redirect(untrusted_base(), fragment, "xyz")),
+            "state in redirect does not match the state sent to authorize_url"
+        );
+
+        let fragment = "access_token=abc&token_type=bearer&expires_in=3600";
+        assert!(ImplicitAuthorizer::from_redirect(untrusted_base(), fragment, "xyz").is_err());
+    }
+
+    #[test]
+    fn from_redirect_rejects_out_of_range_expiry() {
+        for expires_in in ["99999999999999999", "-99999999999999999", "9223372036854775807"] {
+            let fragment = format!(
+                "access_token=abc&token_type=bearer&state=xyz&expires_in={}",
+                expires_in
+            );
+
+            assert_eq!(
+                invalid_invocation(ImplicitAuthorizer::from_redirect(untrusted_base(), fragment.as_str(), "xyz")),
+                "expires_in in redirect is out of range"
+            );
+        }
+    }
+
+    // the grant type and credentials come from runtime config, so nothing
+    // may borrow from them past construction
+    fn authorizer_from_config(grant_type: &str, client_id: String, client_secret: String) -> Box<dyn Authorizer> {
+        match grant_type {
+            "client_credentials" => {
+                let authenticator = TrustedAuthenticator::new(requestor(), &client_id, &client_secret, None);
+                Box::new(ReadOnlyAuthorizer::new(BaseAuthorizer::new(Box::new(authenticator), true), None))
+            }
+            _ => {
+                let authenticator = UntrustedAuthenticator::new(requestor(), &client_id, None);
+                Box::new(DeviceIdAuthorizer::new(BaseAuthorizer::new(Box::new(authenticator), false), None, None))
             }
         }
+    }
+
+    #[test]
+    fn authorizers_can_be_built_from_owned_config() {
+        let trusted = authorizer_from_config("client_credentials", "id".to_string(), "secret".to_string());
+        let untrusted = authorizer_from_config("installed_client", "id".to_string(), String::new());
+
+        assert!(!trusted.is_valid());
+        assert!(!untrusted.is_valid());
+    }
+
+    #[test]
+    fn from_redirect_requires_untrusted_authenticator() {
+        let authenticator = TrustedAuthenticator::new(requestor(), "client", "secret", None);
+        let base = BaseAuthorizer::new(Box::new(authenticator), true);
+        let fragment = "access_token=abc&token_type=bearer&state=xyz&expires_in=3600";
 
-        self.base.request_token(data).await
+        assert!(ImplicitAuthorizer::from_redirect(base, fragment, "xyz").is_err());
     }
 }
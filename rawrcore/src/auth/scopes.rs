@@ -0,0 +1,316 @@
+use lazy_static::lazy_static;
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use crate::errors::RawrCoreError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Account,
+    Creddits,
+    Edit,
+    Flair,
+    History,
+    Identity,
+    LiveManage,
+    ModConfig,
+    ModContributors,
+    ModFlair,
+    ModLog,
+    ModMail,
+    ModNote,
+    ModOthers,
+    ModPosts,
+    ModSelf,
+    ModTraffic,
+    ModWiki,
+    MySubreddits,
+    PrivateMessages,
+    Read,
+    Report,
+    Save,
+    StructuredStyles,
+    Submit,
+    Subscribe,
+    Vote,
+    WikiEdit,
+    WikiRead,
+}
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Account => "account",
+            Scope::Creddits => "creddits",
+            Scope::Edit => "edit",
+            Scope::Flair => "flair",
+            Scope::History => "history",
+            Scope::Identity => "identity",
+            Scope::LiveManage => "livemanage",
+            Scope::ModConfig => "modconfig",
+            Scope::ModContributors => "modcontributors",
+            Scope::ModFlair => "modflair",
+            Scope::ModLog => "modlog",
+            Scope::ModMail => "modmail",
+            Scope::ModNote => "modnote",
+            Scope::ModOthers => "modothers",
+            Scope::ModPosts => "modposts",
+            Scope::ModSelf => "modself",
+            Scope::ModTraffic => "modtraffic",
+            Scope::ModWiki => "modwiki",
+            Scope::MySubreddits => "mysubreddits",
+            Scope::PrivateMessages => "privatemessages",
+            Scope::Read => "read",
+            Scope::Report => "report",
+            Scope::Save => "save",
+            Scope::StructuredStyles => "structuredstyles",
+            Scope::Submit => "submit",
+            Scope::Subscribe => "subscribe",
+            Scope::Vote => "vote",
+            Scope::WikiEdit => "wikiedit",
+            Scope::WikiRead => "wikiread",
+        }
+    }
+
+    // "*" is what reddit reports for tokens granted every scope
+    pub fn is_granted(&self, granted: &HashSet<String>) -> bool {
+        granted.contains("*") || granted.contains(self.as_str())
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = RawrCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "account" => Ok(Scope::Account),
+            "creddits" => Ok(Scope::Creddits),
+            "edit" => Ok(Scope::Edit),
+            "flair" => Ok(Scope::Flair),
+            "history" => Ok(Scope::History),
+            "identity" => Ok(Scope::Identity),
+            "livemanage" => Ok(Scope::LiveManage),
+            "modconfig" => Ok(Scope::ModConfig),
+            "modcontributors" => Ok(Scope::ModContributors),
+            "modflair" => Ok(Scope::ModFlair),
+            "modlog" => Ok(Scope::ModLog),
+            "modmail" => Ok(Scope::ModMail),
+            "modnote" => Ok(Scope::ModNote),
+            "modothers" => Ok(Scope::ModOthers),
+            "modposts" => Ok(Scope::ModPosts),
+            "modself" => Ok(Scope::ModSelf),
+            "modtraffic" => Ok(Scope::ModTraffic),
+            "modwiki" => Ok(Scope::ModWiki),
+            "mysubreddits" => Ok(Scope::MySubreddits),
+            "privatemessages" => Ok(Scope::PrivateMessages),
+            "read" => Ok(Scope::Read),
+            "report" => Ok(Scope::Report),
+            "save" => Ok(Scope::Save),
+            "structuredstyles" => Ok(Scope::StructuredStyles),
+            "submit" => Ok(Scope::Submit),
+            "subscribe" => Ok(Scope::Subscribe),
+            "vote" => Ok(Scope::Vote),
+            "wikiedit" => Ok(Scope::WikiEdit),
+            "wikiread" => Ok(Scope::WikiRead),
+            _ => Err(RawrCoreError::InvalidInvocation("unknown OAuth scope")),
+        }
+    }
+}
+
+lazy_static! {
+    // "*" matches exactly one path segment; the first matching entry wins, so
+    // more specific patterns must come before general ones
+    static ref ENDPOINT_SCOPES: Vec<(&'static str, Scope)> = vec![
+        ("/api/v1/me", Scope::Identity),
+        ("/api/v1/me/karma", Scope::MySubreddits),
+        ("/api/v1/me/trophies", Scope::Identity),
+        ("/api/v1/me/friends", Scope::MySubreddits),
+        ("/api/v1/me/friends/*", Scope::Subscribe),
+        ("/api/v1/user/*/trophies", Scope::Read),
+        ("/api/multi/user/*", Scope::Read),
+        ("/user/*/about", Scope::Read),
+        ("/user/*/*", Scope::History),
+        ("/api/v1/me/prefs", Scope::Identity),
+        ("/subreddits/mine/*", Scope::MySubreddits),
+        ("/api/submit", Scope::Submit),
+        ("/api/submit_poll_post", Scope::Submit),
+        ("/api/submit_gallery_post", Scope::Submit),
+        ("/api/media/asset", Scope::Submit),
+        ("/api/comment", Scope::Submit),
+        ("/api/editusertext", Scope::Edit),
+        ("/api/del", Scope::Edit),
+        ("/api/vote", Scope::Vote),
+        ("/api/save", Scope::Save),
+        ("/api/unsave", Scope::Save),
+        ("/api/saved_categories", Scope::Save),
+        ("/api/hide", Scope::Report),
+        ("/api/unhide", Scope::Report),
+        ("/api/report", Scope::Report),
+        ("/api/compose", Scope::PrivateMessages),
+        ("/api/read_message", Scope::PrivateMessages),
+        ("/api/unread_message", Scope::PrivateMessages),
+        ("/api/read_all_messages", Scope::PrivateMessages),
+        ("/api/block", Scope::PrivateMessages),
+        ("/message/*", Scope::PrivateMessages),
+        ("/api/approve", Scope::ModPosts),
+        ("/api/remove", Scope::ModPosts),
+        ("/api/lock", Scope::ModPosts),
+        ("/api/unlock", Scope::ModPosts),
+        ("/api/distinguish", Scope::ModPosts),
+        ("/api/set_subreddit_sticky", Scope::ModPosts),
+        ("/api/ignore_reports", Scope::ModPosts),
+        ("/api/unignore_reports", Scope::ModPosts),
+        ("/api/marknsfw", Scope::ModPosts),
+        ("/api/unmarknsfw", Scope::ModPosts),
+        ("/api/spoiler", Scope::ModPosts),
+        ("/api/unspoiler", Scope::ModPosts),
+        ("/api/v1/modactions/removal_reasons", Scope::ModPosts),
+        ("/r/*/about/modqueue", Scope::ModPosts),
+        ("/r/*/about/reports", Scope::ModPosts),
+        ("/r/*/about/spam", Scope::ModPosts),
+        ("/r/*/about/edited", Scope::ModPosts),
+        ("/r/*/about/unmoderated", Scope::ModPosts),
+        ("/r/*/about/moderators", Scope::Read),
+        ("/r/*/about/contributors", Scope::Read),
+        ("/r/*/about/banned", Scope::Read),
+        ("/r/*/about/muted", Scope::Read),
+        ("/r/*/about/wikibanned", Scope::Read),
+        ("/r/*/about/wikicontributors", Scope::Read),
+        // the relationship helpers check the scope of each `type` themselves;
+        // these cover bans, mutes and approved users
+        ("/r/*/api/friend", Scope::ModContributors),
+        ("/r/*/api/unfriend", Scope::ModContributors),
+        ("/api/v1/*/moderators_invited", Scope::Read),
+        ("/r/*/api/setpermissions", Scope::ModOthers),
+        ("/r/*/api/accept_moderator_invite", Scope::ModSelf),
+        ("/r/*/about/log", Scope::ModLog),
+        ("/r/*/about/edit", Scope::ModConfig),
+        ("/api/site_admin", Scope::ModConfig),
+        ("/api/add_subreddit_rule", Scope::ModConfig),
+        ("/api/update_subreddit_rule", Scope::ModConfig),
+        ("/api/remove_subreddit_rule", Scope::ModConfig),
+        ("/api/reorder_subreddit_rules", Scope::ModConfig),
+        ("/api/v1/*/removal_reasons", Scope::ModConfig),
+        ("/api/v1/*/removal_reasons/*", Scope::ModConfig),
+        ("/r/*/about/rules", Scope::Read),
+        ("/api/mod/conversations", Scope::ModMail),
+        ("/api/mod/conversations/*", Scope::ModMail),
+        ("/api/mod/conversations/*/*", Scope::ModMail),
+        ("/api/mod/notes", Scope::ModNote),
+        ("/api/mod/notes/recent", Scope::ModNote),
+        ("/r/*/api/link_flair_v2", Scope::Flair),
+        ("/r/*/api/user_flair_v2", Scope::Flair),
+        ("/r/*/api/selectflair", Scope::Flair),
+        ("/r/*/api/flairtemplate_v2", Scope::ModFlair),
+        ("/r/*/api/deleteflairtemplate", Scope::ModFlair),
+        ("/r/*/api/clearflairtemplates", Scope::ModFlair),
+        ("/r/*/api/flaircsv", Scope::ModFlair),
+        ("/r/*/api/flairlist", Scope::ModFlair),
+        ("/r/*/api/deleteflair", Scope::ModFlair),
+        ("/r/*/api/wiki/edit", Scope::WikiEdit),
+        ("/r/*/api/wiki/revert", Scope::ModWiki),
+        ("/r/*/api/wiki/alloweditor/*", Scope::ModWiki),
+        ("/r/*/wiki/settings/*", Scope::ModWiki),
+        ("/r/*/wiki/settings/*/*", Scope::ModWiki),
+        ("/r/*/wiki/*", Scope::WikiRead),
+        ("/r/*/wiki/*/*", Scope::WikiRead),
+        ("/r/*/wiki/*/*/*", Scope::WikiRead),
+        ("/r/*/about", Scope::Read),
+        ("/comments/*", Scope::Read),
+        ("/api/info", Scope::Read),
+        ("/search", Scope::Read),
+        ("/r/*/search", Scope::Read),
+        ("/subreddits/*", Scope::Read),
+        ("/api/search_reddit_names", Scope::Read),
+        ("/api/subreddit_autocomplete_v2", Scope::Read),
+        ("/api/morechildren", Scope::Read),
+    ];
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or_default();
+    let path = path.trim_end_matches('/').trim_end_matches(".json");
+
+    let pattern_segments: Vec<&str> = pattern.split('/').collect();
+    let path_segments: Vec<&str> = path.split('/').collect();
+
+    pattern_segments.len() == path_segments.len()
+        && pattern_segments
+            .iter()
+            .zip(path_segments.iter())
+            .all(|(pattern_segment, path_segment)| {
+                pattern_segment.eq(&"*") || pattern_segment.eq(path_segment)
+            })
+}
+
+pub fn required_scope(path: &str) -> Option<Scope> {
+    ENDPOINT_SCOPES
+        .iter()
+        .find(|(pattern, _)| path_matches(pattern, path))
+        .map(|(_, scope)| *scope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards_match_exactly_one_segment() {
+        assert!(path_matches("/r/*/about", "/r/rust/about"));
+        assert!(!path_matches("/r/*/about", "/r/about"));
+        assert!(!path_matches("/r/*/about", "/r/rust/about/rules"));
+        assert!(!path_matches("/r/*/about", "/r/rust/wiki"));
+    }
+
+    #[test]
+    fn query_strings_suffixes_and_trailing_slashes_are_ignored() {
+        assert!(path_matches("/api/v1/me", "/api/v1/me/"));
+        assert!(path_matches("/api/info", "/api/info.json"));
+        assert!(path_matches("/api/info", "/api/info?id=t3_abc"));
+    }
+
+    #[test]
+    fn first_matching_entry_wins() {
+        assert_eq!(required_scope("/user/spez/about"), Some(Scope::Read));
+        assert_eq!(required_scope("/user/spez/saved"), Some(Scope::History));
+        assert_eq!(required_scope("/r/rust/wiki/settings/index"), Some(Scope::ModWiki));
+        assert_eq!(required_scope("/r/rust/wiki/index"), Some(Scope::WikiRead));
+    }
+
+    #[test]
+    fn unknown_paths_have_no_scope() {
+        assert_eq!(required_scope("/api/not_an_endpoint"), None);
+    }
+
+    #[test]
+    fn scopes_round_trip_through_strings() {
+        for scope in [Scope::ModPosts, Scope::WikiRead, Scope::PrivateMessages] {
+            assert_eq!(scope.as_str().parse::<Scope>().ok(), Some(scope));
+        }
+
+        assert!("notascope".parse::<Scope>().is_err());
+    }
+
+    #[test]
+    fn wildcard_grants_every_scope() {
+        let everything: HashSet<String> = ["*".to_string()].into();
+        let read_only: HashSet<String> = ["read".to_string()].into();
+
+        assert!(Scope::ModConfig.is_granted(&everything));
+        assert!(Scope::Read.is_granted(&read_only));
+        assert!(!Scope::ModConfig.is_granted(&read_only));
+    }
+
+    #[test]
+    fn mod_queues_need_modposts() {
+        for queue in ["modqueue", "reports", "spam", "edited", "unmoderated"] {
+            let path = format!("/r/rust/about/{}", queue);
+
+            assert_eq!(required_scope(path.as_str()), Some(Scope::ModPosts), "{}", queue);
+        }
+    }
+}
@@ -4,5 +4,53 @@ lazy_static! {
     pub static ref ACCESS_TOKEN_PATH: String = "/api/v1/access_token".to_string();
     pub static ref AUTHORIZATION_PATH: String = "/api/v1/authorize".to_string();
     pub static ref REVOKE_TOKEN_PATH: String = "/api/v1/revoke_token".to_string();  
+    pub static ref ME_PATH: String = "/api/v1/me".to_string();
+    pub static ref MORE_CHILDREN_PATH: String = "/api/morechildren".to_string();
+    pub static ref SUBMIT_PATH: String = "/api/submit".to_string();
+    pub static ref SUBMIT_POLL_PATH: String = "/api/submit_poll_post".to_string();
+    pub static ref SUBMIT_GALLERY_PATH: String = "/api/submit_gallery_post.json".to_string();
+    pub static ref MEDIA_ASSET_PATH: String = "/api/media/asset.json".to_string();
+    pub static ref COMMENT_PATH: String = "/api/comment".to_string();
+    pub static ref EDIT_USER_TEXT_PATH: String = "/api/editusertext".to_string();
+    pub static ref DELETE_PATH: String = "/api/del".to_string();
+    pub static ref VOTE_PATH: String = "/api/vote".to_string();
+    pub static ref SAVE_PATH: String = "/api/save".to_string();
+    pub static ref UNSAVE_PATH: String = "/api/unsave".to_string();
+    pub static ref SAVED_CATEGORIES_PATH: String = "/api/saved_categories".to_string();
+    pub static ref HIDE_PATH: String = "/api/hide".to_string();
+    pub static ref UNHIDE_PATH: String = "/api/unhide".to_string();
+    pub static ref REPORT_PATH: String = "/api/report".to_string();
+    pub static ref COMPOSE_PATH: String = "/api/compose".to_string();
+    pub static ref READ_MESSAGE_PATH: String = "/api/read_message".to_string();
+    pub static ref UNREAD_MESSAGE_PATH: String = "/api/unread_message".to_string();
+    pub static ref READ_ALL_MESSAGES_PATH: String = "/api/read_all_messages".to_string();
+    pub static ref BLOCK_PATH: String = "/api/block".to_string();
+    pub static ref APPROVE_PATH: String = "/api/approve".to_string();
+    pub static ref REMOVE_PATH: String = "/api/remove".to_string();
+    pub static ref REMOVAL_REASONS_PATH: String = "/api/v1/modactions/removal_reasons".to_string();
+    pub static ref LOCK_PATH: String = "/api/lock".to_string();
+    pub static ref UNLOCK_PATH: String = "/api/unlock".to_string();
+    pub static ref DISTINGUISH_PATH: String = "/api/distinguish".to_string();
+    pub static ref STICKY_PATH: String = "/api/set_subreddit_sticky".to_string();
+    pub static ref IGNORE_REPORTS_PATH: String = "/api/ignore_reports".to_string();
+    pub static ref UNIGNORE_REPORTS_PATH: String = "/api/unignore_reports".to_string();
+    pub static ref MARK_NSFW_PATH: String = "/api/marknsfw".to_string();
+    pub static ref UNMARK_NSFW_PATH: String = "/api/unmarknsfw".to_string();
+    pub static ref SPOILER_PATH: String = "/api/spoiler".to_string();
+    pub static ref UNSPOILER_PATH: String = "/api/unspoiler".to_string();
+    pub static ref MODMAIL_CONVERSATIONS_PATH: String = "/api/mod/conversations".to_string();
+    pub static ref SITE_ADMIN_PATH: String = "/api/site_admin".to_string();
+    pub static ref ADD_RULE_PATH: String = "/api/add_subreddit_rule".to_string();
+    pub static ref UPDATE_RULE_PATH: String = "/api/update_subreddit_rule".to_string();
+    pub static ref REMOVE_RULE_PATH: String = "/api/remove_subreddit_rule".to_string();
+    pub static ref REORDER_RULES_PATH: String = "/api/reorder_subreddit_rules".to_string();
+    pub static ref MOD_NOTES_PATH: String = "/api/mod/notes".to_string();
+    pub static ref RECENT_MOD_NOTES_PATH: String = "/api/mod/notes/recent".to_string();
+    pub static ref INFO_PATH: String = "/api/info".to_string();
+    pub static ref SEARCH_PATH: String = "/search".to_string();
+    pub static ref SEARCH_SUBREDDITS_PATH: String = "/subreddits/search".to_string();
+    pub static ref SEARCH_REDDIT_NAMES_PATH: String = "/api/search_reddit_names".to_string();
+    pub static ref USERNAME_AVAILABLE_PATH: String = "/api/username_available".to_string();
+    pub static ref SUBREDDIT_AUTOCOMPLETE_PATH: String = "/api/subreddit_autocomplete_v2".to_string();
     pub static ref TIMEOUT: f32 = 16.0;  
 }
\ No newline at end of file
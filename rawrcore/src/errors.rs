@@ -1,46 +1,176 @@
-pub enum RawrCoreError<'r> {
-    InvalidInvocation(&'r str),
+use std::collections::HashSet;
+
+use crate::auth::scopes::Scope;
+
+pub enum RawrCoreError {
+    InvalidInvocation(&'static str),
     RequestError(RequestErrorData),
-    ResponseError(ResponseErrorData<'r>),
-    OAuthError(OAuthErrorData<'r>),
-    BadJSON(ResponseErrorData<'r>),
-    BadRequest(ResponseErrorData<'r>),
-    Conflict(ResponseErrorData<'r>),
-    Forbidden(ResponseErrorData<'r>),
-    InsufficientScope(ResponseErrorData<'r>),
-    InvalidToken(ResponseErrorData<'r>),
-    NotFound(ResponseErrorData<'r>),
-    Redirect(RedirectData<'r>),
-    ServerError(ResponseErrorData<'r>),
-    SpecialError(SpecialErrorData<'r>),
-    TooLarge(ResponseErrorData<'r>),
-    TooManyRequests(ResponseErrorData<'r>),
-    UnavailableForLegalReasons(ResponseErrorData<'r>),
-    URITooLong(ResponseErrorData<'r>)
+    ResponseError(ResponseErrorData),
+    OAuthError(OAuthErrorData),
+    ReauthorizationRequired(&'static str),
+    BadJSON(BadJSONData),
+    RedditAPIError(RedditAPIErrorData),
+    BadRequest(ResponseErrorData),
+    Conflict(ConflictData),
+    Forbidden(ResponseErrorData),
+    InsufficientScope(InsufficientScopeData),
+    InvalidToken(ResponseErrorData),
+    InvalidAutoModeratorConfig(AutoModeratorConfigErrorData),
+    MediaProcessingFailed(MediaProcessingData),
+    MediaProcessingTimeout(MediaProcessingData),
+    NotFound(ResponseErrorData),
+    Redirect(RedirectData),
+    ServerError(ResponseErrorData),
+    ShadowbannedUser(RedditorErrorData),
+    SpecialError(SpecialErrorData),
+    SuspendedUser(RedditorErrorData),
+    TooLarge(ResponseErrorData),
+    TooManyRequests(ResponseErrorData),
+    UnavailableForLegalReasons(ResponseErrorData),
+    URITooLong(ResponseErrorData)
 }
 
 pub struct RequestErrorData {
     pub original_error: reqwest::Error,
-    pub request: reqwest::Request
+    pub request: Option<reqwest::Request>
+}
+
+pub struct ResponseErrorData {
+    pub response: reqwest::Response,
+}
+
+pub struct ConflictData {
+    // not kept when the body was read to fill in `edit`
+    pub response: Option<reqwest::Response>,
+    // set when a wiki edit raced another edit
+    pub edit: Option<EditConflictData>
+}
+
+pub struct EditConflictData {
+    pub submitted: String,
+    pub current: String,
+    pub current_revision: Option<String>
+}
+
+pub struct InsufficientScopeData {
+    pub required: Option<Scope>,
+    pub granted: HashSet<String>,
+    pub response: Option<reqwest::Response>
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RedditErrorType {
+    AlreadySubmitted,
+    DeletedComment,
+    DeletedLink,
+    NoLinks,
+    NoSelfs,
+    NoText,
+    NotAuthor,
+    RateLimit,
+    SubredditNoExist,
+    SubredditNotAllowed,
+    ThreadLocked,
+    TooLong,
+    TooOld,
+    UserRequired,
+    Unknown(String)
+}
+impl RedditErrorType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RedditErrorType::AlreadySubmitted => "ALREADY_SUB",
+            RedditErrorType::DeletedComment => "DELETED_COMMENT",
+            RedditErrorType::DeletedLink => "DELETED_LINK",
+            RedditErrorType::NoLinks => "NO_LINKS",
+            RedditErrorType::NoSelfs => "NO_SELFS",
+            RedditErrorType::NoText => "NO_TEXT",
+            RedditErrorType::NotAuthor => "NOT_AUTHOR",
+            RedditErrorType::RateLimit => "RATELIMIT",
+            RedditErrorType::SubredditNoExist => "SUBREDDIT_NOEXIST",
+            RedditErrorType::SubredditNotAllowed => "SUBREDDIT_NOTALLOWED",
+            RedditErrorType::ThreadLocked => "THREAD_LOCKED",
+            RedditErrorType::TooLong => "TOO_LONG",
+            RedditErrorType::TooOld => "TOO_OLD",
+            RedditErrorType::UserRequired => "USER_REQUIRED",
+            RedditErrorType::Unknown(error_type) => error_type.as_str(),
+        }
+    }
+}
+
+impl From<&str> for RedditErrorType {
+    fn from(error_type: &str) -> Self {
+        match error_type {
+            "ALREADY_SUB" => RedditErrorType::AlreadySubmitted,
+            "DELETED_COMMENT" => RedditErrorType::DeletedComment,
+            "DELETED_LINK" => RedditErrorType::DeletedLink,
+            "NO_LINKS" => RedditErrorType::NoLinks,
+            "NO_SELFS" => RedditErrorType::NoSelfs,
+            "NO_TEXT" => RedditErrorType::NoText,
+            "NOT_AUTHOR" => RedditErrorType::NotAuthor,
+            "RATELIMIT" => RedditErrorType::RateLimit,
+            "SUBREDDIT_NOEXIST" => RedditErrorType::SubredditNoExist,
+            "SUBREDDIT_NOTALLOWED" => RedditErrorType::SubredditNotAllowed,
+            "THREAD_LOCKED" => RedditErrorType::ThreadLocked,
+            "TOO_LONG" => RedditErrorType::TooLong,
+            "TOO_OLD" => RedditErrorType::TooOld,
+            "USER_REQUIRED" => RedditErrorType::UserRequired,
+            _ => RedditErrorType::Unknown(error_type.to_string()),
+        }
+    }
+}
+
+pub struct RedditErrorItem {
+    pub error_type: RedditErrorType,
+    pub message: String,
+    pub field: Option<String>
+}
+
+pub struct RedditAPIErrorData {
+    pub errors: Vec<RedditErrorItem>
+}
+impl RedditAPIErrorData {
+    pub fn contains(&self, error_type: &RedditErrorType) -> bool {
+        self.errors.iter().any(|error| error.error_type.eq(error_type))
+    }
+}
+
+pub struct AutoModeratorConfigErrorData {
+    // zero based index of the offending `---` separated rule
+    pub rule: usize,
+    pub message: String
+}
+
+// shadowbanned and deleted accounts cannot be told apart; reddit answers
+// 404 for both
+pub struct RedditorErrorData {
+    pub name: String
+}
+
+// a media post is not known to exist; `user_submitted_page` can be checked
+// for it by hand
+pub struct MediaProcessingData {
+    pub websocket_url: Option<String>,
+    pub user_submitted_page: Option<String>
 }
 
-pub struct ResponseErrorData<'r> {
-    pub response: &'r reqwest::Response,
+pub struct BadJSONData {
+    pub body: String,
+    pub original_error: serde_json::Error
 }
 
-pub struct OAuthErrorData<'r> {
-    pub response: &'r reqwest::Response,
+pub struct OAuthErrorData {
     pub error: String,
     pub description: Option<String>
 }
 
-pub struct RedirectData<'r> {
-    pub response: &'r reqwest::Response,
+pub struct RedirectData {
+    pub response: reqwest::Response,
     pub path: String
 }
 
-pub struct SpecialErrorData<'r> {
-    pub response: &'r reqwest::Response,
+pub struct SpecialErrorData {
+    pub response: reqwest::Response,
     pub retry_after: u16,
     pub message: String
 }
\ No newline at end of file
@@ -1,8 +1,17 @@
+#![allow(clippy::result_large_err)]
+
 pub mod errors;
 mod util;
-mod sessions;
-mod requestor;
+pub mod sessions;
+pub mod requestor;
 mod constants;
-mod auth;
-mod rate_limit;
-mod models;
\ No newline at end of file
+pub mod api;
+pub mod auth;
+pub mod rate_limit;
+pub mod session_pool;
+pub mod stream;
+pub mod models;
+pub mod pagination;
+pub mod upload;
+#[cfg(test)]
+mod test_util;
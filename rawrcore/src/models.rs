@@ -1,4 +1,19 @@
+pub mod comments;
+pub mod flair;
+pub mod fullname;
+pub mod mod_action;
+pub mod mod_note;
+pub mod modmail;
+pub mod multireddit;
+pub mod relationship;
+pub mod rules;
+pub mod subreddit_settings;
+pub mod things;
+pub mod wiki;
 
+use serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize)]
 pub struct RequestTokenResponse {
     pub expires_in: i32,
     pub error: Option<String>,
@@ -6,4 +21,50 @@ pub struct RequestTokenResponse {
     pub access_token: String,
     pub refresh_token: Option<String>,
     pub scope: String
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Identity {
+    pub id: String,
+    pub name: String,
+    pub link_karma: i64,
+    pub comment_karma: i64,
+    pub total_karma: Option<i64>,
+}
+
+#[derive(Clone)]
+pub struct Listing<T> {
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub dist: Option<i64>,
+    pub children: Vec<T>,
+}
+
+// listings always arrive wrapped as {"kind": "Listing", "data": {...}}
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Listing<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(bound(deserialize = "T: Deserialize<'de>"))]
+        struct ListingData<T> {
+            after: Option<String>,
+            before: Option<String>,
+            dist: Option<i64>,
+            children: Vec<T>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(bound(deserialize = "T: Deserialize<'de>"))]
+        struct ListingEnvelope<T> {
+            data: ListingData<T>,
+        }
+
+        let envelope = ListingEnvelope::<T>::deserialize(deserializer)?;
+
+        Ok(Self {
+            after: envelope.data.after,
+            before: envelope.data.before,
+            dist: envelope.data.dist,
+            children: envelope.data.children,
+        })
+    }
+}
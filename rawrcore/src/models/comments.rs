@@ -0,0 +1,647 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::{cmp::Reverse, collections::VecDeque};
+
+use crate::{
+    constants,
+    errors::RawrCoreError,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+use super::{
+    fullname::{Fullname, ThingKind},
+    things::{Comment, Link, Thing},
+    Listing,
+};
+
+const MORE_CHILDREN_BATCH_SIZE: usize = 100;
+
+#[derive(Deserialize, Clone)]
+pub struct MoreComments {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Fullname,
+    pub count: i64,
+    pub depth: Option<i64>,
+    pub children: Vec<String>,
+}
+impl MoreComments {
+    // "continue this thread" stubs have no children listed and must be
+    // fetched through the permalink of their parent instead
+    pub fn is_continue_this_thread(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", content = "data")]
+enum RawCommentNode {
+    #[serde(rename = "t1")]
+    Comment(Box<Comment>),
+    #[serde(rename = "more")]
+    More(MoreComments),
+}
+
+#[derive(Clone)]
+pub enum CommentNode {
+    Comment(Box<CommentTree>),
+    More(MoreComments),
+}
+
+#[derive(Clone)]
+pub struct CommentTree {
+    pub comment: Comment,
+    pub replies: Vec<CommentNode>,
+}
+
+fn build_nodes(raw: Vec<RawCommentNode>) -> Result<Vec<CommentNode>, RawrCoreError> {
+    let mut nodes = vec![];
+
+    for node in raw {
+        match node {
+            RawCommentNode::Comment(mut comment) => {
+                // replies are "" when empty and a nested Listing otherwise
+                let replies = match comment.replies.take() {
+                    Value::Object(map) => {
+                        let listing =
+                            util::from_json_value::<Listing<RawCommentNode>>(Value::Object(map))?;
+                        build_nodes(listing.children)?
+                    }
+                    _ => vec![],
+                };
+
+                nodes.push(CommentNode::Comment(Box::new(CommentTree {
+                    comment: *comment,
+                    replies,
+                })));
+            }
+            RawCommentNode::More(more) => nodes.push(CommentNode::More(more)),
+        }
+    }
+
+    Ok(nodes)
+}
+
+// where a MoreComments stub sat: the comment it replied to, or the top
+// level, and its index among its siblings
+struct MoreLocation {
+    parent: Option<Fullname>,
+    index: usize,
+}
+
+fn take_more(
+    nodes: &mut Vec<CommentNode>,
+    parent: Option<&Fullname>,
+    id: &str,
+) -> Option<(MoreLocation, MoreComments)> {
+    let position = nodes
+        .iter()
+        .position(|node| matches!(node, CommentNode::More(more) if more.id.eq(id)));
+
+    if let Some(index) = position {
+        let location = MoreLocation {
+            parent: parent.cloned(),
+            index,
+        };
+
+        return match nodes.remove(index) {
+            CommentNode::More(more) => Some((location, more)),
+            CommentNode::Comment(_) => None,
+        };
+    }
+
+    for node in nodes.iter_mut() {
+        if let CommentNode::Comment(tree) = node {
+            let found = take_more(&mut tree.replies, Some(&tree.comment.name), id);
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+
+    None
+}
+
+fn find_replies_mut<'a>(
+    nodes: &'a mut [CommentNode],
+    parent: &Fullname,
+) -> Option<&'a mut Vec<CommentNode>> {
+    for node in nodes.iter_mut() {
+        if let CommentNode::Comment(tree) = node {
+            if tree.comment.name.eq(parent) {
+                return Some(&mut tree.replies);
+            }
+
+            let found = find_replies_mut(&mut tree.replies, parent);
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+
+    None
+}
+
+fn collect_more(nodes: &[CommentNode], found: &mut Vec<MoreComments>) {
+    let mut queue: VecDeque<&CommentNode> = nodes.iter().collect();
+
+    while let Some(node) = queue.pop_front() {
+        match node {
+            CommentNode::Comment(tree) => queue.extend(tree.replies.iter()),
+            CommentNode::More(more) => found.push(more.clone()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CommentForest {
+    pub submission: Link,
+    pub comments: Vec<CommentNode>,
+}
+
+impl CommentForest {
+    // /comments/{id} responds with [submission listing, comment listing]
+    pub fn from_value(value: Value) -> Result<Self, RawrCoreError> {
+        let (submission, comments) =
+            util::from_json_value::<(Listing<Thing>, Listing<RawCommentNode>)>(value)?;
+
+        let submission = match submission.children.into_iter().next() {
+            Some(Thing::Link(link)) => *link,
+            _ => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "comments response did not start with a submission",
+                ))
+            }
+        };
+
+        Ok(Self {
+            submission,
+            comments: build_nodes(comments.children)?,
+        })
+    }
+
+    pub async fn fetch<R: RetryStrategy>(
+        session: &mut Session<R>,
+        submission_id: &str,
+        sort: Option<&str>,
+    ) -> Result<Self, RawrCoreError> {
+        let path = format!("/comments/{}", submission_id);
+        let mut params = vec![];
+
+        if let Some(sort) = sort {
+            params.push(("sort", sort));
+        }
+
+        let value = session
+            .request::<(), (), (), Vec<(&str, &str)>>(
+                RequestMethod::Get,
+                path.as_str(),
+                None,
+                None,
+                None,
+                Some(&params),
+                None,
+            )
+            .await?;
+
+        Self::from_value(value)
+    }
+
+    pub fn dfs(&self) -> DepthFirstIter<'_> {
+        DepthFirstIter {
+            stack: self.comments.iter().rev().collect(),
+        }
+    }
+
+    pub fn bfs(&self) -> BreadthFirstIter<'_> {
+        BreadthFirstIter {
+            queue: self.comments.iter().collect(),
+        }
+    }
+
+    pub fn more_comments(&self) -> Vec<MoreComments> {
+        let mut found = vec![];
+        collect_more(&self.comments, &mut found);
+
+        found
+    }
+
+    fn insert(&mut self, node: CommentNode, parent: &Fullname) {
+        if parent.kind() == ThingKind::Link {
+            self.comments.push(node);
+            return;
+        }
+
+        match find_replies_mut(&mut self.comments, parent) {
+            Some(replies) => replies.push(node),
+            // orphaned children are kept at the top level rather than dropped
+            None => self.comments.push(node),
+        }
+    }
+
+    // puts a stub back where `take_more` found it. expanding only ever
+    // appends replies, so the index still holds
+    fn restore_more(&mut self, location: MoreLocation, more: MoreComments) {
+        let siblings = match &location.parent {
+            Some(parent) => match find_replies_mut(&mut self.comments, parent) {
+                Some(replies) => replies,
+                None => &mut self.comments,
+            },
+            None => &mut self.comments,
+        };
+        let index = location.index.min(siblings.len());
+
+        siblings.insert(index, CommentNode::More(more));
+    }
+
+    // children are removed from `more` as they are inserted, so on error it
+    // lists only the ones still to be fetched
+    async fn expand<R: RetryStrategy>(
+        &mut self,
+        session: &mut Session<R>,
+        more: &mut MoreComments,
+    ) -> Result<(), RawrCoreError> {
+        if more.is_continue_this_thread() {
+            if more.parent_id.kind() == ThingKind::Link {
+                return Ok(());
+            }
+
+            let parent = CommentForest::fetch_thread(session, &self.submission, &more.parent_id).await?;
+
+            for node in parent {
+                self.insert(node, &more.parent_id);
+            }
+
+            return Ok(());
+        }
+
+        let link_id = self.submission.name.to_string();
+
+        while !more.children.is_empty() {
+            let batch_size = more.children.len().min(MORE_CHILDREN_BATCH_SIZE);
+            let children = more.children[..batch_size].join(",");
+            let params = [
+                ("api_type", "json"),
+                ("link_id", link_id.as_str()),
+                ("children", children.as_str()),
+                ("limit_children", "false"),
+            ];
+
+            let value = session
+                .request::<(), (), (), [(&str, &str); 4]>(
+                    RequestMethod::Get,
+                    constants::MORE_CHILDREN_PATH.as_str(),
+                    None,
+                    None,
+                    None,
+                    Some(&params),
+                    None,
+                )
+                .await?;
+
+            let things = util::from_json_value::<Vec<RawCommentNode>>(
+                value["json"]["data"]["things"].clone(),
+            )?;
+
+            // morechildren returns a flat list in which parents always
+            // precede their children
+            for node in build_nodes(things)? {
+                let parent = match &node {
+                    CommentNode::Comment(tree) => tree.comment.parent_id.clone(),
+                    CommentNode::More(more) => more.parent_id.clone(),
+                };
+
+                self.insert(node, &parent);
+            }
+
+            more.children.drain(..batch_size);
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_thread<R: RetryStrategy>(
+        session: &mut Session<R>,
+        submission: &Link,
+        parent: &Fullname,
+    ) -> Result<Vec<CommentNode>, RawrCoreError> {
+        let path = format!("/comments/{}/_/{}", submission.id, parent.id());
+        let value = session
+            .request::<(), (), (), ()>(RequestMethod::Get, path.as_str(), None, None, None, None, None)
+            .await?;
+        let forest = Self::from_value(value)?;
+
+        match forest.comments.into_iter().next() {
+            Some(CommentNode::Comment(tree)) => Ok(tree.replies),
+            _ => Ok(vec![]),
+        }
+    }
+
+    // replaces up to `limit` MoreComments stubs (all of them when None) whose
+    // count is at least `threshold`, largest first, and returns the stubs that
+    // were left in the tree. a stub that fails to expand stays in the tree
+    pub async fn replace_more<R: RetryStrategy>(
+        &mut self,
+        session: &mut Session<R>,
+        limit: Option<usize>,
+        threshold: i64,
+    ) -> Result<Vec<MoreComments>, RawrCoreError> {
+        let mut replaced = 0;
+
+        loop {
+            let mut candidates: Vec<MoreComments> = self
+                .more_comments()
+                .into_iter()
+                .filter(|more| more.count >= threshold)
+                .collect();
+
+            if candidates.is_empty() || limit.is_some_and(|limit| replaced >= limit) {
+                break;
+            }
+
+            candidates.sort_by_key(|more| Reverse(more.count));
+
+            for more in candidates {
+                if limit.is_some_and(|limit| replaced >= limit) {
+                    break;
+                }
+
+                if let Some((location, mut more)) = take_more(&mut self.comments, None, more.id.as_str()) {
+                    // the stub goes back in the tree so the caller can retry
+                    if let Err(e) = self.expand(session, &mut more).await {
+                        self.restore_more(location, more);
+                        return Err(e);
+                    }
+
+                    replaced += 1;
+                }
+            }
+        }
+
+        Ok(self.more_comments())
+    }
+}
+
+pub struct DepthFirstIter<'a> {
+    stack: Vec<&'a CommentNode>,
+}
+
+impl<'a> Iterator for DepthFirstIter<'a> {
+    type Item = &'a Comment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            if let CommentNode::Comment(tree) = node {
+                self.stack.extend(tree.replies.iter().rev());
+                return Some(&tree.comment);
+            }
+        }
+
+        None
+    }
+}
+
+pub struct BreadthFirstIter<'a> {
+    queue: VecDeque<&'a CommentNode>,
+}
+
+impl<'a> Iterator for BreadthFirstIter<'a> {
+    type Item = &'a Comment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.queue.pop_front() {
+            if let CommentNode::Comment(tree) = node {
+                self.queue.extend(tree.replies.iter());
+                return Some(&tree.comment);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, MockServer};
+    use serde_json::json;
+
+    fn listing(children: Vec<Value>) -> Value {
+        json!({
+            "kind": "Listing",
+            "data": {"after": null, "before": null, "dist": null, "children": children},
+        })
+    }
+
+    fn comment(id: &str, parent: &str, replies: Vec<Value>) -> Value {
+        let replies = match replies.is_empty() {
+            true => json!(""),
+            false => listing(replies),
+        };
+
+        json!({"kind": "t1", "data": {
+            "id": id,
+            "name": format!("t1_{}", id),
+            "author": "spez",
+            "body": id,
+            "link_id": "t3_post",
+            "parent_id": parent,
+            "subreddit": "rust",
+            "subreddit_id": "t5_2qh1i",
+            "score": 1,
+            "created_utc": 1.0,
+            "replies": replies,
+        }})
+    }
+
+    fn more(id: &str, parent: &str, count: i64, children: &[&str]) -> Value {
+        json!({"kind": "more", "data": {
+            "id": id,
+            "name": format!("t1_{}", id),
+            "parent_id": parent,
+            "count": count,
+            "depth": 1,
+            "children": children,
+        }})
+    }
+
+    fn thread(comments: Vec<Value>) -> Value {
+        let submission = json!({"kind": "t3", "data": {
+            "id": "post",
+            "name": "t3_post",
+            "title": "a post",
+            "author": "spez",
+            "subreddit": "rust",
+            "subreddit_id": "t5_2qh1i",
+            "permalink": "/r/rust/comments/post/a_post/",
+            "score": 1,
+            "created_utc": 1.0,
+        }});
+
+        json!([listing(vec![submission]), listing(comments)])
+    }
+
+    // a > (b > c, more), f
+    fn forest(stub: Value) -> CommentForest {
+        CommentForest::from_value(thread(vec![
+            comment(
+                "a",
+                "t3_post",
+                vec![comment("b", "t1_a", vec![comment("c", "t1_b", vec![])]), stub],
+            ),
+            comment("f", "t3_post", vec![]),
+        ]))
+        .ok()
+        .unwrap()
+    }
+
+    fn ids<'a>(comments: impl Iterator<Item = &'a Comment>) -> Vec<&'a str> {
+        comments.map(|comment| comment.id.as_str()).collect()
+    }
+
+    fn replies<'a>(nodes: &'a [CommentNode], id: &str) -> &'a [CommentNode] {
+        fn find<'a>(nodes: &'a [CommentNode], id: &str) -> Option<&'a [CommentNode]> {
+            nodes.iter().find_map(|node| match node {
+                CommentNode::Comment(tree) if tree.comment.id.eq(id) => Some(tree.replies.as_slice()),
+                CommentNode::Comment(tree) => find(&tree.replies, id),
+                CommentNode::More(_) => None,
+            })
+        }
+
+        match find(nodes, id) {
+            Some(replies) => replies,
+            None => panic!("expected comment {} in the tree", id),
+        }
+    }
+
+    // comment ids, and stub ids prefixed with "more:"
+    fn node_ids(nodes: &[CommentNode]) -> Vec<String> {
+        nodes
+            .iter()
+            .map(|node| match node {
+                CommentNode::Comment(tree) => tree.comment.id.clone(),
+                CommentNode::More(more) => format!("more:{}", more.id),
+            })
+            .collect()
+    }
+
+    fn replace_more(
+        forest: &mut CommentForest,
+        server: &MockServer,
+    ) -> Result<Vec<MoreComments>, RawrCoreError> {
+        let mut session = server.session();
+
+        test_util::runtime().block_on(forest.replace_more(&mut session, None, 0))
+    }
+
+    #[test]
+    fn from_value_builds_the_tree() {
+        let forest = forest(more("m", "t1_a", 2, &["d", "e"]));
+
+        assert_eq!(forest.submission.name.to_string(), "t3_post");
+        assert_eq!(node_ids(&forest.comments), ["a", "f"]);
+        assert_eq!(node_ids(replies(&forest.comments, "a")), ["b", "more:m"]);
+        assert_eq!(node_ids(replies(&forest.comments, "b")), ["c"]);
+
+        let stubs = forest.more_comments();
+        assert_eq!(stubs.len(), 1);
+        assert_eq!(stubs[0].children, ["d", "e"]);
+        assert!(!stubs[0].is_continue_this_thread());
+    }
+
+    #[test]
+    fn traversals_visit_comments_in_order() {
+        let forest = forest(more("m", "t1_a", 2, &["d", "e"]));
+
+        assert_eq!(ids(forest.dfs()), ["a", "b", "c", "f"]);
+        assert_eq!(ids(forest.bfs()), ["a", "f", "b", "c"]);
+    }
+
+    #[test]
+    fn expanded_children_go_under_their_parents() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/api/morechildren" => (
+                200,
+                json!({"json": {"errors": [], "data": {"things": [
+                    comment("d", "t1_a", vec![]),
+                    comment("e", "t1_d", vec![]),
+                ]}}}),
+            ),
+            _ => (404, Value::Null),
+        });
+        let mut forest = forest(more("m", "t1_a", 2, &["d", "e"]));
+
+        let left = replace_more(&mut forest, &server).ok().unwrap();
+
+        assert!(left.is_empty());
+        assert_eq!(node_ids(replies(&forest.comments, "a")), ["b", "d"]);
+        assert_eq!(node_ids(replies(&forest.comments, "d")), ["e"]);
+        assert_eq!(ids(forest.dfs()), ["a", "b", "c", "d", "e", "f"]);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].param("link_id"), Some("t3_post"));
+        assert_eq!(requests[0].param("children"), Some("d,e"));
+    }
+
+    #[test]
+    fn continue_this_thread_fetches_the_parent() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/comments/post/_/c" => (
+                200,
+                thread(vec![comment("c", "t1_b", vec![comment("g", "t1_c", vec![])])]),
+            ),
+            _ => (404, Value::Null),
+        });
+        let mut forest = CommentForest::from_value(thread(vec![comment(
+            "b",
+            "t3_post",
+            vec![comment("c", "t1_b", vec![more("m", "t1_c", 0, &[])])],
+        )]))
+        .ok()
+        .unwrap();
+        assert!(forest.more_comments()[0].is_continue_this_thread());
+
+        let left = replace_more(&mut forest, &server).ok().unwrap();
+
+        assert!(left.is_empty());
+        assert_eq!(node_ids(replies(&forest.comments, "c")), ["g"]);
+    }
+
+    #[test]
+    fn failed_expansions_keep_their_stub() {
+        let server = MockServer::start(|_| (404, Value::Null));
+        let mut forest = forest(more("m", "t1_a", 2, &["d", "e"]));
+
+        assert!(matches!(
+            replace_more(&mut forest, &server),
+            Err(RawrCoreError::NotFound(_))
+        ));
+        assert_eq!(node_ids(replies(&forest.comments, "a")), ["b", "more:m"]);
+        assert_eq!(forest.more_comments()[0].children, ["d", "e"]);
+    }
+
+    #[test]
+    fn partly_expanded_stubs_keep_the_unfetched_children() {
+        let mut batches = 0;
+        let server = MockServer::start(move |_| {
+            batches += 1;
+            match batches {
+                1 => (200, json!({"json": {"errors": [], "data": {"things": []}}})),
+                _ => (404, Value::Null),
+            }
+        });
+
+        let children: Vec<String> = (0..150).map(|n| format!("c{}", n)).collect();
+        let children: Vec<&str> = children.iter().map(|child| child.as_str()).collect();
+        let mut forest = forest(more("m", "t1_a", 150, &children));
+
+        assert!(replace_more(&mut forest, &server).is_err());
+
+        let stubs = forest.more_comments();
+        assert_eq!(stubs.len(), 1);
+        assert_eq!(stubs[0].children.len(), 50);
+        assert_eq!(stubs[0].children[0], "c100");
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FlairTextColor {
+    Light,
+    Dark,
+}
+impl FlairTextColor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FlairTextColor::Light => "light",
+            FlairTextColor::Dark => "dark",
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FlairAllowableContent {
+    All,
+    Emoji,
+    Text,
+}
+impl FlairAllowableContent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FlairAllowableContent::All => "all",
+            FlairAllowableContent::Emoji => "emoji",
+            FlairAllowableContent::Text => "text",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "e")]
+pub enum FlairRichText {
+    #[serde(rename = "text")]
+    Text {
+        #[serde(rename = "t")]
+        text: String,
+    },
+    // `name` is the emoji's placeholder, e.g. ":snoo:"
+    #[serde(rename = "emoji")]
+    Emoji {
+        #[serde(rename = "a")]
+        name: String,
+        #[serde(rename = "u", skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+}
+impl FlairRichText {
+    // flair text refers to emojis by placeholder, so richtext flattens into
+    // plain text losslessly
+    pub fn to_text(parts: &[FlairRichText]) -> String {
+        parts
+            .iter()
+            .map(|part| match part {
+                FlairRichText::Text { text } => text.as_str(),
+                FlairRichText::Emoji { name, .. } => name.as_str(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct FlairTemplate {
+    pub id: String,
+    #[serde(default)]
+    pub text: String,
+    pub text_color: Option<FlairTextColor>,
+    pub background_color: Option<String>,
+    pub css_class: Option<String>,
+    #[serde(default)]
+    pub text_editable: bool,
+    #[serde(default)]
+    pub mod_only: bool,
+    pub allowable_content: Option<FlairAllowableContent>,
+    pub max_emojis: Option<u8>,
+    #[serde(default)]
+    pub richtext: Vec<FlairRichText>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct UserFlair {
+    pub user: String,
+    pub flair_text: Option<String>,
+    pub flair_css_class: Option<String>,
+}
+
+// one page of a subreddit's flairlist
+#[derive(Deserialize)]
+pub struct FlairListResponse {
+    pub(crate) users: Vec<UserFlair>,
+    pub(crate) next: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FlairCsvRow {
+    pub(crate) ok: bool,
+    #[serde(default)]
+    pub(crate) status: String,
+    #[serde(default)]
+    pub(crate) errors: HashMap<String, String>,
+    #[serde(default)]
+    pub(crate) warnings: HashMap<String, String>,
+}
+
+// the outcome of one row of a bulk flair assignment
+#[derive(Clone, Debug)]
+pub struct FlairAssignmentResult {
+    pub user: String,
+    pub ok: bool,
+    pub status: String,
+    pub errors: HashMap<String, String>,
+    pub warnings: HashMap<String, String>,
+}
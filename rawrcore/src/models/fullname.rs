@@ -0,0 +1,201 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use crate::errors::RawrCoreError;
+
+const BASE36_DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+pub fn to_base36(mut n: u64) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+
+    let mut digits = vec![];
+    while n > 0 {
+        digits.push(BASE36_DIGITS[(n % 36) as usize]);
+        n /= 36;
+    }
+    digits.reverse();
+
+    String::from_utf8(digits).expect("base36 digits are ascii")
+}
+
+// only the lowercase digits reddit uses; `from_str_radix` alone would also
+// take uppercase and a leading sign
+pub fn from_base36(s: &str) -> Option<u64> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit() || c.is_ascii_lowercase()) {
+        return None;
+    }
+
+    u64::from_str_radix(s, 36).ok()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ThingKind {
+    Comment,
+    Account,
+    Link,
+    Message,
+    Subreddit,
+    Award,
+}
+impl ThingKind {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            ThingKind::Comment => "t1",
+            ThingKind::Account => "t2",
+            ThingKind::Link => "t3",
+            ThingKind::Message => "t4",
+            ThingKind::Subreddit => "t5",
+            ThingKind::Award => "t6",
+        }
+    }
+}
+
+impl FromStr for ThingKind {
+    type Err = RawrCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "t1" => Ok(ThingKind::Comment),
+            "t2" => Ok(ThingKind::Account),
+            "t3" => Ok(ThingKind::Link),
+            "t4" => Ok(ThingKind::Message),
+            "t5" => Ok(ThingKind::Subreddit),
+            "t6" => Ok(ThingKind::Award),
+            _ => Err(RawrCoreError::InvalidInvocation("unknown thing kind prefix")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fullname {
+    kind: ThingKind,
+    id: String,
+}
+impl Fullname {
+    pub fn new(kind: ThingKind, id: &str) -> Result<Self, RawrCoreError> {
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_digit() || c.is_ascii_lowercase())
+        {
+            return Err(RawrCoreError::InvalidInvocation(
+                "thing ids must be non-empty lowercase base36",
+            ));
+        }
+
+        Ok(Self {
+            kind,
+            id: id.to_string(),
+        })
+    }
+
+    pub fn from_id_number(kind: ThingKind, id: u64) -> Self {
+        Self {
+            kind,
+            id: to_base36(id),
+        }
+    }
+
+    pub fn kind(&self) -> ThingKind {
+        self.kind
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn id_number(&self) -> Option<u64> {
+        from_base36(self.id.as_str())
+    }
+}
+
+impl FromStr for Fullname {
+    type Err = RawrCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('_') {
+            Some((prefix, id)) => Fullname::new(prefix.parse::<ThingKind>()?, id),
+            None => Err(RawrCoreError::InvalidInvocation(
+                "fullnames must look like t3_abc123",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Fullname {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.kind.prefix(), self.id)
+    }
+}
+
+impl Serialize for Fullname {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fullname {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+
+        match s.parse::<Fullname>() {
+            Ok(fullname) => Ok(fullname),
+            Err(_) => Err(de::Error::custom(format!("invalid fullname: {}", s))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base36_round_trips() {
+        for n in [0, 1, 35, 36, 1295, 1296, 123_456_789, u64::MAX] {
+            assert_eq!(from_base36(to_base36(n).as_str()), Some(n));
+        }
+
+        assert_eq!(to_base36(35), "z");
+        assert_eq!(to_base36(36), "10");
+    }
+
+    #[test]
+    fn from_base36_rejects_non_base36() {
+        assert_eq!(from_base36(""), None);
+        assert_eq!(from_base36("ABC"), None);
+        assert_eq!(from_base36("+1"), None);
+        assert_eq!(from_base36("a_b"), None);
+        // one past u64::MAX
+        assert_eq!(from_base36("3w5e11264sgsg"), None);
+    }
+
+    #[test]
+    fn fullnames_round_trip() {
+        let fullname = "t3_15bfi0".parse::<Fullname>().ok().unwrap();
+
+        assert_eq!(fullname.kind(), ThingKind::Link);
+        assert_eq!(fullname.id(), "15bfi0");
+        assert_eq!(fullname.to_string(), "t3_15bfi0");
+        assert_eq!(Fullname::from_id_number(ThingKind::Link, fullname.id_number().unwrap()), fullname);
+    }
+
+    #[test]
+    fn fullnames_round_trip_through_serde() {
+        let fullname = Fullname::new(ThingKind::Comment, "c0ffee").ok().unwrap();
+        let json = serde_json::to_string(&fullname).ok().unwrap();
+
+        assert_eq!(json, r#""t1_c0ffee""#);
+        assert_eq!(serde_json::from_str::<Fullname>(json.as_str()).ok(), Some(fullname));
+    }
+
+    #[test]
+    fn invalid_fullnames_are_rejected() {
+        for invalid in ["t9_abc", "x3_abc", "t3abc", "t3_", "t3_ABC", "t3_a-b", ""] {
+            assert!(invalid.parse::<Fullname>().is_err(), "{} was accepted", invalid);
+        }
+
+        assert!(serde_json::from_str::<Fullname>(r#""t7_abc""#).is_err());
+    }
+}
@@ -0,0 +1,529 @@
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+
+use super::fullname::Fullname;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ModActionType {
+    BanUser,
+    UnbanUser,
+    SpamLink,
+    RemoveLink,
+    ApproveLink,
+    SpamComment,
+    RemoveComment,
+    ApproveComment,
+    AddModerator,
+    ShowComment,
+    InviteModerator,
+    UninviteModerator,
+    AcceptModeratorInvite,
+    RemoveModerator,
+    AddContributor,
+    RemoveContributor,
+    EditSettings,
+    EditFlair,
+    Distinguish,
+    MarkNsfw,
+    WikiBanned,
+    WikiContributor,
+    WikiUnbanned,
+    WikiPageListed,
+    RemoveWikiContributor,
+    WikiRevise,
+    WikiPermLevel,
+    IgnoreReports,
+    UnignoreReports,
+    SetPermissions,
+    SetSuggestedSort,
+    Sticky,
+    Unsticky,
+    SetContestMode,
+    UnsetContestMode,
+    Lock,
+    Unlock,
+    MuteUser,
+    UnmuteUser,
+    CreateRule,
+    EditRule,
+    ReorderRules,
+    DeleteRule,
+    Spoiler,
+    Unspoiler,
+    ModmailEnrollment,
+    CommunityStyling,
+    CommunityWidgets,
+    MarkOriginalContent,
+    Collections,
+    Events,
+    HiddenAward,
+    AddCommunityTopics,
+    RemoveCommunityTopics,
+    CreateScheduledPost,
+    EditScheduledPost,
+    DeleteScheduledPost,
+    SubmitScheduledPost,
+    EditPostRequirements,
+    InviteSubscriber,
+    SubmitContentRatingSurvey,
+    AdjustPostCrowdControlLevel,
+    EnablePostCrowdControlFilter,
+    DisablePostCrowdControlFilter,
+    DeleteOverriddenClassification,
+    OverrideClassification,
+    ReorderModerators,
+    RequestAssistance,
+    SnoozeReports,
+    UnsnoozeReports,
+    AddNote,
+    DeleteNote,
+    AddRemovalReason,
+    CreateRemovalReason,
+    UpdateRemovalReason,
+    DeleteRemovalReason,
+    ReorderRemovalReason,
+    DevPlatformAppChanged,
+    DevPlatformAppDisabled,
+    DevPlatformAppEnabled,
+    DevPlatformAppInstalled,
+    DevPlatformAppUninstalled,
+    EditSavedResponse,
+    ChatApproveMessage,
+    ChatRemoveMessage,
+    ChatBanUser,
+    ChatUnbanUser,
+    ChatInviteHost,
+    ChatRemoveHost,
+    ApproveAward,
+    Unknown(String),
+}
+impl ModActionType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ModActionType::BanUser => "banuser",
+            ModActionType::UnbanUser => "unbanuser",
+            ModActionType::SpamLink => "spamlink",
+            ModActionType::RemoveLink => "removelink",
+            ModActionType::ApproveLink => "approvelink",
+            ModActionType::SpamComment => "spamcomment",
+            ModActionType::RemoveComment => "removecomment",
+            ModActionType::ApproveComment => "approvecomment",
+            ModActionType::AddModerator => "addmoderator",
+            ModActionType::ShowComment => "showcomment",
+            ModActionType::InviteModerator => "invitemoderator",
+            ModActionType::UninviteModerator => "uninvitemoderator",
+            ModActionType::AcceptModeratorInvite => "acceptmoderatorinvite",
+            ModActionType::RemoveModerator => "removemoderator",
+            ModActionType::AddContributor => "addcontributor",
+            ModActionType::RemoveContributor => "removecontributor",
+            ModActionType::EditSettings => "editsettings",
+            ModActionType::EditFlair => "editflair",
+            ModActionType::Distinguish => "distinguish",
+            ModActionType::MarkNsfw => "marknsfw",
+            ModActionType::WikiBanned => "wikibanned",
+            ModActionType::WikiContributor => "wikicontributor",
+            ModActionType::WikiUnbanned => "wikiunbanned",
+            ModActionType::WikiPageListed => "wikipagelisted",
+            ModActionType::RemoveWikiContributor => "removewikicontributor",
+            ModActionType::WikiRevise => "wikirevise",
+            ModActionType::WikiPermLevel => "wikipermlevel",
+            ModActionType::IgnoreReports => "ignorereports",
+            ModActionType::UnignoreReports => "unignorereports",
+            ModActionType::SetPermissions => "setpermissions",
+            ModActionType::SetSuggestedSort => "setsuggestedsort",
+            ModActionType::Sticky => "sticky",
+            ModActionType::Unsticky => "unsticky",
+            ModActionType::SetContestMode => "setcontestmode",
+            ModActionType::UnsetContestMode => "unsetcontestmode",
+            ModActionType::Lock => "lock",
+            ModActionType::Unlock => "unlock",
+            ModActionType::MuteUser => "muteuser",
+            ModActionType::UnmuteUser => "unmuteuser",
+            ModActionType::CreateRule => "createrule",
+            ModActionType::EditRule => "editrule",
+            ModActionType::ReorderRules => "reorderrules",
+            ModActionType::DeleteRule => "deleterule",
+            ModActionType::Spoiler => "spoiler",
+            ModActionType::Unspoiler => "unspoiler",
+            ModActionType::ModmailEnrollment => "modmail_enrollment",
+            ModActionType::CommunityStyling => "community_styling",
+            ModActionType::CommunityWidgets => "community_widgets",
+            ModActionType::MarkOriginalContent => "markoriginalcontent",
+            ModActionType::Collections => "collections",
+            ModActionType::Events => "events",
+            ModActionType::HiddenAward => "hidden_award",
+            ModActionType::AddCommunityTopics => "add_community_topics",
+            ModActionType::RemoveCommunityTopics => "remove_community_topics",
+            ModActionType::CreateScheduledPost => "create_scheduled_post",
+            ModActionType::EditScheduledPost => "edit_scheduled_post",
+            ModActionType::DeleteScheduledPost => "delete_scheduled_post",
+            ModActionType::SubmitScheduledPost => "submit_scheduled_post",
+            ModActionType::EditPostRequirements => "edit_post_requirements",
+            ModActionType::InviteSubscriber => "invitesubscriber",
+            ModActionType::SubmitContentRatingSurvey => "submit_content_rating_survey",
+            ModActionType::AdjustPostCrowdControlLevel => "adjust_post_crowd_control_level",
+            ModActionType::EnablePostCrowdControlFilter => "enable_post_crowd_control_filter",
+            ModActionType::DisablePostCrowdControlFilter => "disable_post_crowd_control_filter",
+            ModActionType::DeleteOverriddenClassification => "deleteoverriddenclassification",
+            ModActionType::OverrideClassification => "overrideclassification",
+            ModActionType::ReorderModerators => "reordermoderators",
+            ModActionType::RequestAssistance => "request_assistance",
+            ModActionType::SnoozeReports => "snoozereports",
+            ModActionType::UnsnoozeReports => "unsnoozereports",
+            ModActionType::AddNote => "addnote",
+            ModActionType::DeleteNote => "deletenote",
+            ModActionType::AddRemovalReason => "addremovalreason",
+            ModActionType::CreateRemovalReason => "createremovalreason",
+            ModActionType::UpdateRemovalReason => "updateremovalreason",
+            ModActionType::DeleteRemovalReason => "deleteremovalreason",
+            ModActionType::ReorderRemovalReason => "reorderremovalreason",
+            ModActionType::DevPlatformAppChanged => "dev_platform_app_changed",
+            ModActionType::DevPlatformAppDisabled => "dev_platform_app_disabled",
+            ModActionType::DevPlatformAppEnabled => "dev_platform_app_enabled",
+            ModActionType::DevPlatformAppInstalled => "dev_platform_app_installed",
+            ModActionType::DevPlatformAppUninstalled => "dev_platform_app_uninstalled",
+            ModActionType::EditSavedResponse => "edit_saved_response",
+            ModActionType::ChatApproveMessage => "chat_approve_message",
+            ModActionType::ChatRemoveMessage => "chat_remove_message",
+            ModActionType::ChatBanUser => "chat_ban_user",
+            ModActionType::ChatUnbanUser => "chat_unban_user",
+            ModActionType::ChatInviteHost => "chat_invite_host",
+            ModActionType::ChatRemoveHost => "chat_remove_host",
+            ModActionType::ApproveAward => "approve_award",
+            ModActionType::Unknown(action) => action.as_str(),
+        }
+    }
+}
+
+impl From<&str> for ModActionType {
+    fn from(action: &str) -> Self {
+        match action {
+            "banuser" => ModActionType::BanUser,
+            "unbanuser" => ModActionType::UnbanUser,
+            "spamlink" => ModActionType::SpamLink,
+            "removelink" => ModActionType::RemoveLink,
+            "approvelink" => ModActionType::ApproveLink,
+            "spamcomment" => ModActionType::SpamComment,
+            "removecomment" => ModActionType::RemoveComment,
+            "approvecomment" => ModActionType::ApproveComment,
+            "addmoderator" => ModActionType::AddModerator,
+            "showcomment" => ModActionType::ShowComment,
+            "invitemoderator" => ModActionType::InviteModerator,
+            "uninvitemoderator" => ModActionType::UninviteModerator,
+            "acceptmoderatorinvite" => ModActionType::AcceptModeratorInvite,
+            "removemoderator" => ModActionType::RemoveModerator,
+            "addcontributor" => ModActionType::AddContributor,
+            "removecontributor" => ModActionType::RemoveContributor,
+            "editsettings" => ModActionType::EditSettings,
+            "editflair" => ModActionType::EditFlair,
+            "distinguish" => ModActionType::Distinguish,
+            "marknsfw" => ModActionType::MarkNsfw,
+            "wikibanned" => ModActionType::WikiBanned,
+            "wikicontributor" => ModActionType::WikiContributor,
+            "wikiunbanned" => ModActionType::WikiUnbanned,
+            "wikipagelisted" => ModActionType::WikiPageListed,
+            "removewikicontributor" => ModActionType::RemoveWikiContributor,
+            "wikirevise" => ModActionType::WikiRevise,
+            "wikipermlevel" => ModActionType::WikiPermLevel,
+            "ignorereports" => ModActionType::IgnoreReports,
+            "unignorereports" => ModActionType::UnignoreReports,
+            "setpermissions" => ModActionType::SetPermissions,
+            "setsuggestedsort" => ModActionType::SetSuggestedSort,
+            "sticky" => ModActionType::Sticky,
+            "unsticky" => ModActionType::Unsticky,
+            "setcontestmode" => ModActionType::SetContestMode,
+            "unsetcontestmode" => ModActionType::UnsetContestMode,
+            "lock" => ModActionType::Lock,
+            "unlock" => ModActionType::Unlock,
+            "muteuser" => ModActionType::MuteUser,
+            "unmuteuser" => ModActionType::UnmuteUser,
+            "createrule" => ModActionType::CreateRule,
+            "editrule" => ModActionType::EditRule,
+            "reorderrules" => ModActionType::ReorderRules,
+            "deleterule" => ModActionType::DeleteRule,
+            "spoiler" => ModActionType::Spoiler,
+            "unspoiler" => ModActionType::Unspoiler,
+            "modmail_enrollment" => ModActionType::ModmailEnrollment,
+            "community_styling" => ModActionType::CommunityStyling,
+            "community_widgets" => ModActionType::CommunityWidgets,
+            "markoriginalcontent" => ModActionType::MarkOriginalContent,
+            "collections" => ModActionType::Collections,
+            "events" => ModActionType::Events,
+            "hidden_award" => ModActionType::HiddenAward,
+            "add_community_topics" => ModActionType::AddCommunityTopics,
+            "remove_community_topics" => ModActionType::RemoveCommunityTopics,
+            "create_scheduled_post" => ModActionType::CreateScheduledPost,
+            "edit_scheduled_post" => ModActionType::EditScheduledPost,
+            "delete_scheduled_post" => ModActionType::DeleteScheduledPost,
+            "submit_scheduled_post" => ModActionType::SubmitScheduledPost,
+            "edit_post_requirements" => ModActionType::EditPostRequirements,
+            "invitesubscriber" => ModActionType::InviteSubscriber,
+            "submit_content_rating_survey" => ModActionType::SubmitContentRatingSurvey,
+            "adjust_post_crowd_control_level" => ModActionType::AdjustPostCrowdControlLevel,
+            "enable_post_crowd_control_filter" => ModActionType::EnablePostCrowdControlFilter,
+            "disable_post_crowd_control_filter" => ModActionType::DisablePostCrowdControlFilter,
+            "deleteoverriddenclassification" => ModActionType::DeleteOverriddenClassification,
+            "overrideclassification" => ModActionType::OverrideClassification,
+            "reordermoderators" => ModActionType::ReorderModerators,
+            "request_assistance" => ModActionType::RequestAssistance,
+            "snoozereports" => ModActionType::SnoozeReports,
+            "unsnoozereports" => ModActionType::UnsnoozeReports,
+            "addnote" => ModActionType::AddNote,
+            "deletenote" => ModActionType::DeleteNote,
+            "addremovalreason" => ModActionType::AddRemovalReason,
+            "createremovalreason" => ModActionType::CreateRemovalReason,
+            "updateremovalreason" => ModActionType::UpdateRemovalReason,
+            "deleteremovalreason" => ModActionType::DeleteRemovalReason,
+            "reorderremovalreason" => ModActionType::ReorderRemovalReason,
+            "dev_platform_app_changed" => ModActionType::DevPlatformAppChanged,
+            "dev_platform_app_disabled" => ModActionType::DevPlatformAppDisabled,
+            "dev_platform_app_enabled" => ModActionType::DevPlatformAppEnabled,
+            "dev_platform_app_installed" => ModActionType::DevPlatformAppInstalled,
+            "dev_platform_app_uninstalled" => ModActionType::DevPlatformAppUninstalled,
+            "edit_saved_response" => ModActionType::EditSavedResponse,
+            "chat_approve_message" => ModActionType::ChatApproveMessage,
+            "chat_remove_message" => ModActionType::ChatRemoveMessage,
+            "chat_ban_user" => ModActionType::ChatBanUser,
+            "chat_unban_user" => ModActionType::ChatUnbanUser,
+            "chat_invite_host" => ModActionType::ChatInviteHost,
+            "chat_remove_host" => ModActionType::ChatRemoveHost,
+            "approve_award" => ModActionType::ApproveAward,
+            _ => ModActionType::Unknown(action.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ModActionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ModActionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let action = String::deserialize(deserializer)?;
+
+        Ok(ModActionType::from(action.as_str()))
+    }
+}
+
+#[derive(Clone)]
+pub struct ModAction {
+    pub id: String,
+    pub action: ModActionType,
+    pub moderator: String,
+    pub mod_id36: Option<String>,
+    pub created_utc: f64,
+    pub subreddit: String,
+    pub sr_id36: Option<String>,
+    pub target_author: Option<String>,
+    pub target_fullname: Option<String>,
+    pub target_permalink: Option<String>,
+    pub target_title: Option<String>,
+    pub target_body: Option<String>,
+    pub details: Option<String>,
+    pub description: Option<String>,
+}
+impl ModAction {
+    // the target is missing for actions on the subreddit itself (settings,
+    // rules, wiki pages, ...)
+    pub fn target(&self) -> Option<Fullname> {
+        self.target_fullname
+            .as_deref()
+            .and_then(|fullname| fullname.parse::<Fullname>().ok())
+    }
+}
+
+// mod log entries arrive wrapped as {"kind": "modaction", "data": {...}}
+impl<'de> Deserialize<'de> for ModAction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct ModActionData {
+            id: String,
+            action: ModActionType,
+            #[serde(rename = "mod")]
+            moderator: String,
+            mod_id36: Option<String>,
+            created_utc: f64,
+            subreddit: String,
+            sr_id36: Option<String>,
+            target_author: Option<String>,
+            target_fullname: Option<String>,
+            target_permalink: Option<String>,
+            target_title: Option<String>,
+            target_body: Option<String>,
+            details: Option<String>,
+            description: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct ModActionEnvelope {
+            data: ModActionData,
+        }
+
+        let data = ModActionEnvelope::deserialize(deserializer)?.data;
+
+        Ok(Self {
+            id: data.id,
+            action: data.action,
+            moderator: data.moderator,
+            mod_id36: data.mod_id36,
+            created_utc: data.created_utc,
+            subreddit: data.subreddit,
+            sr_id36: data.sr_id36,
+            target_author: data.target_author,
+            target_fullname: data.target_fullname,
+            target_permalink: data.target_permalink,
+            target_title: data.target_title,
+            target_body: data.target_body,
+            details: data.details,
+            description: data.description,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    const KNOWN: &[ModActionType] = &[
+        ModActionType::BanUser,
+        ModActionType::UnbanUser,
+        ModActionType::SpamLink,
+        ModActionType::RemoveLink,
+        ModActionType::ApproveLink,
+        ModActionType::SpamComment,
+        ModActionType::RemoveComment,
+        ModActionType::ApproveComment,
+        ModActionType::AddModerator,
+        ModActionType::ShowComment,
+        ModActionType::InviteModerator,
+        ModActionType::UninviteModerator,
+        ModActionType::AcceptModeratorInvite,
+        ModActionType::RemoveModerator,
+        ModActionType::AddContributor,
+        ModActionType::RemoveContributor,
+        ModActionType::EditSettings,
+        ModActionType::EditFlair,
+        ModActionType::Distinguish,
+        ModActionType::MarkNsfw,
+        ModActionType::WikiBanned,
+        ModActionType::WikiContributor,
+        ModActionType::WikiUnbanned,
+        ModActionType::WikiPageListed,
+        ModActionType::RemoveWikiContributor,
+        ModActionType::WikiRevise,
+        ModActionType::WikiPermLevel,
+        ModActionType::IgnoreReports,
+        ModActionType::UnignoreReports,
+        ModActionType::SetPermissions,
+        ModActionType::SetSuggestedSort,
+        ModActionType::Sticky,
+        ModActionType::Unsticky,
+        ModActionType::SetContestMode,
+        ModActionType::UnsetContestMode,
+        ModActionType::Lock,
+        ModActionType::Unlock,
+        ModActionType::MuteUser,
+        ModActionType::UnmuteUser,
+        ModActionType::CreateRule,
+        ModActionType::EditRule,
+        ModActionType::ReorderRules,
+        ModActionType::DeleteRule,
+        ModActionType::Spoiler,
+        ModActionType::Unspoiler,
+        ModActionType::ModmailEnrollment,
+        ModActionType::CommunityStyling,
+        ModActionType::CommunityWidgets,
+        ModActionType::MarkOriginalContent,
+        ModActionType::Collections,
+        ModActionType::Events,
+        ModActionType::HiddenAward,
+        ModActionType::AddCommunityTopics,
+        ModActionType::RemoveCommunityTopics,
+        ModActionType::CreateScheduledPost,
+        ModActionType::EditScheduledPost,
+        ModActionType::DeleteScheduledPost,
+        ModActionType::SubmitScheduledPost,
+        ModActionType::EditPostRequirements,
+        ModActionType::InviteSubscriber,
+        ModActionType::SubmitContentRatingSurvey,
+        ModActionType::AdjustPostCrowdControlLevel,
+        ModActionType::EnablePostCrowdControlFilter,
+        ModActionType::DisablePostCrowdControlFilter,
+        ModActionType::DeleteOverriddenClassification,
+        ModActionType::OverrideClassification,
+        ModActionType::ReorderModerators,
+        ModActionType::RequestAssistance,
+        ModActionType::SnoozeReports,
+        ModActionType::UnsnoozeReports,
+        ModActionType::AddNote,
+        ModActionType::DeleteNote,
+        ModActionType::AddRemovalReason,
+        ModActionType::CreateRemovalReason,
+        ModActionType::UpdateRemovalReason,
+        ModActionType::DeleteRemovalReason,
+        ModActionType::ReorderRemovalReason,
+        ModActionType::DevPlatformAppChanged,
+        ModActionType::DevPlatformAppDisabled,
+        ModActionType::DevPlatformAppEnabled,
+        ModActionType::DevPlatformAppInstalled,
+        ModActionType::DevPlatformAppUninstalled,
+        ModActionType::EditSavedResponse,
+        ModActionType::ChatApproveMessage,
+        ModActionType::ChatRemoveMessage,
+        ModActionType::ChatBanUser,
+        ModActionType::ChatUnbanUser,
+        ModActionType::ChatInviteHost,
+        ModActionType::ChatRemoveHost,
+        ModActionType::ApproveAward,
+    ];
+
+    #[test]
+    fn known_actions_round_trip() {
+        let mut names = HashSet::new();
+
+        for action in KNOWN {
+            assert_eq!(&ModActionType::from(action.as_str()), action);
+            assert!(names.insert(action.as_str()), "{} is used twice", action);
+        }
+    }
+
+    #[test]
+    fn unknown_actions_keep_their_name() {
+        let action = ModActionType::from("brand_new_action");
+
+        assert_eq!(
+            action,
+            ModActionType::Unknown("brand_new_action".to_string())
+        );
+        assert_eq!(action.as_str(), "brand_new_action");
+    }
+
+    #[test]
+    fn mod_actions_unwrap_their_envelope() {
+        let action: ModAction = util::from_json_value(json!({
+            "kind": "modaction",
+            "data": {
+                "id": "ModAction_1",
+                "action": "removelink",
+                "mod": "spez",
+                "created_utc": 1.0,
+                "subreddit": "rust",
+                "target_fullname": "t3_abc",
+            },
+        }))
+        .ok()
+        .unwrap();
+
+        assert_eq!(action.action, ModActionType::RemoveLink);
+        assert_eq!(action.moderator, "spez");
+        assert_eq!(
+            action.target().map(|target| target.to_string()),
+            Some("t3_abc".to_string())
+        );
+    }
+}
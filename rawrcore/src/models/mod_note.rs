@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+use super::fullname::Fullname;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ModNoteLabel {
+    BotBan,
+    PermaBan,
+    Ban,
+    AbuseWarning,
+    SpamWarning,
+    SpamWatch,
+    SolidContributor,
+    HelpfulUser,
+    // labels reddit added after this was written
+    #[serde(other)]
+    Unknown,
+}
+impl ModNoteLabel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModNoteLabel::BotBan => "BOT_BAN",
+            ModNoteLabel::PermaBan => "PERMA_BAN",
+            ModNoteLabel::Ban => "BAN",
+            ModNoteLabel::AbuseWarning => "ABUSE_WARNING",
+            ModNoteLabel::SpamWarning => "SPAM_WARNING",
+            ModNoteLabel::SpamWatch => "SPAM_WATCH",
+            ModNoteLabel::SolidContributor => "SOLID_CONTRIBUTOR",
+            ModNoteLabel::HelpfulUser => "HELPFUL_USER",
+            ModNoteLabel::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+// notes are either written by moderators or recorded automatically for
+// mod actions taken against the user
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ModNoteType {
+    Note,
+    Approval,
+    Removal,
+    Ban,
+    Mute,
+    Invite,
+    Spam,
+    ContentChange,
+    ModAction,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct UserNoteData {
+    pub note: Option<String>,
+    pub label: Option<ModNoteLabel>,
+    // the submission or comment the note is about
+    pub reddit_id: Option<Fullname>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ModActionData {
+    pub action: Option<String>,
+    pub reddit_id: Option<Fullname>,
+    pub details: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ModNote {
+    pub id: String,
+    pub subreddit: String,
+    pub subreddit_id: Fullname,
+    pub user: String,
+    pub user_id: Fullname,
+    pub operator: String,
+    pub operator_id: Fullname,
+    #[serde(rename = "type")]
+    pub note_type: ModNoteType,
+    pub created_at: i64,
+    pub cursor: Option<String>,
+    pub user_note_data: Option<UserNoteData>,
+    pub mod_action_data: Option<ModActionData>,
+}
+
+// one page of a user's notes
+#[derive(Deserialize)]
+pub struct ModNotesResponse {
+    pub(crate) mod_notes: Vec<ModNote>,
+    pub(crate) end_cursor: Option<String>,
+    #[serde(default)]
+    pub(crate) has_next_page: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CreatedModNote {
+    pub(crate) created: ModNote,
+}
+
+// one entry per requested subreddit and user pair, null when the pair has
+// no notes
+#[derive(Deserialize)]
+pub(crate) struct RecentModNotesResponse {
+    pub(crate) mod_notes: Vec<Option<ModNote>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn unknown_labels_do_not_fail_deserialization() {
+        let label = serde_json::from_str::<ModNoteLabel>(r#""SOME_NEW_LABEL""#).ok();
+
+        assert_eq!(label, Some(ModNoteLabel::Unknown));
+    }
+
+    #[test]
+    fn known_labels_round_trip() {
+        let known = [
+            ModNoteLabel::BotBan,
+            ModNoteLabel::PermaBan,
+            ModNoteLabel::Ban,
+            ModNoteLabel::AbuseWarning,
+            ModNoteLabel::SpamWarning,
+            ModNoteLabel::SpamWatch,
+            ModNoteLabel::SolidContributor,
+            ModNoteLabel::HelpfulUser,
+        ];
+
+        for label in known {
+            let parsed = serde_json::from_value::<ModNoteLabel>(json!(label.as_str())).ok();
+
+            assert_eq!(parsed, Some(label));
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::{errors::RawrCoreError, util};
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailAuthor {
+    pub name: String,
+    #[serde(default)]
+    pub is_mod: bool,
+    #[serde(default)]
+    pub is_admin: bool,
+    #[serde(default)]
+    pub is_op: bool,
+    #[serde(default)]
+    pub is_participant: bool,
+    #[serde(default)]
+    pub is_hidden: bool,
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailOwner {
+    pub id: String,
+    pub display_name: String,
+    #[serde(rename = "type")]
+    pub owner_type: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailMessage {
+    pub id: String,
+    pub body: String,
+    pub body_markdown: String,
+    pub author: ModmailAuthor,
+    pub date: String,
+    #[serde(default)]
+    pub is_internal: bool,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailAction {
+    pub id: String,
+    pub date: String,
+    pub action_type_id: i64,
+    pub author: ModmailAuthor,
+}
+
+#[derive(Deserialize, Clone)]
+struct ModmailObjectId {
+    id: String,
+    key: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversation {
+    pub id: String,
+    pub subject: String,
+    // 0 new, 1 in progress, 2 archived
+    pub state: i64,
+    pub last_updated: Option<String>,
+    pub last_user_update: Option<String>,
+    pub last_mod_update: Option<String>,
+    #[serde(default)]
+    pub is_internal: bool,
+    #[serde(default)]
+    pub is_highlighted: bool,
+    #[serde(default)]
+    pub is_auto: bool,
+    #[serde(default)]
+    pub num_messages: i64,
+    pub owner: ModmailOwner,
+    pub participant: Option<ModmailAuthor>,
+    #[serde(default)]
+    pub authors: Vec<ModmailAuthor>,
+    #[serde(default)]
+    obj_ids: Vec<ModmailObjectId>,
+    // filled in from the response's id-keyed maps, oldest first
+    #[serde(skip)]
+    pub messages: Vec<ModmailMessage>,
+    #[serde(skip)]
+    pub mod_actions: Vec<ModmailAction>,
+}
+
+// one page of conversations
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationsResponse {
+    conversations: HashMap<String, Conversation>,
+    #[serde(default)]
+    conversation_ids: Vec<String>,
+    #[serde(default)]
+    messages: HashMap<String, ModmailMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConversationResponse {
+    conversation: Conversation,
+    #[serde(default)]
+    messages: HashMap<String, ModmailMessage>,
+    #[serde(default)]
+    mod_actions: HashMap<String, ModmailAction>,
+}
+
+impl Conversation {
+    // messages and mod actions are sent separately, keyed by id, and the
+    // conversation only references them through `objIds`
+    fn attach(
+        &mut self,
+        messages: &HashMap<String, ModmailMessage>,
+        mod_actions: &HashMap<String, ModmailAction>,
+    ) {
+        for object in self.obj_ids.iter() {
+            match object.key.as_str() {
+                "messages" => {
+                    if let Some(message) = messages.get(&object.id) {
+                        self.messages.push(message.clone());
+                    }
+                }
+                "modActions" => {
+                    if let Some(action) = mod_actions.get(&object.id) {
+                        self.mod_actions.push(action.clone());
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // a single conversation, as returned by reads and conversation actions
+    pub fn from_value(value: Value) -> Result<Self, RawrCoreError> {
+        let response = util::from_json_value::<ConversationResponse>(value)?;
+        let mut conversation = response.conversation;
+        conversation.attach(&response.messages, &response.mod_actions);
+
+        Ok(conversation)
+    }
+
+    // a page of conversations, in the order reddit listed them. listings
+    // only include the most recent message of each conversation
+    pub fn list_from_value(value: Value) -> Result<Vec<Self>, RawrCoreError> {
+        Ok(util::from_json_value::<ConversationsResponse>(value)?.into_conversations())
+    }
+}
+
+impl ConversationsResponse {
+    pub(crate) fn into_conversations(mut self) -> Vec<Conversation> {
+        let mod_actions = HashMap::new();
+        let mut conversations = vec![];
+
+        for id in self.conversation_ids.iter() {
+            if let Some(mut conversation) = self.conversations.remove(id) {
+                conversation.attach(&self.messages, &mod_actions);
+                conversations.push(conversation);
+            }
+        }
+
+        conversations
+    }
+}
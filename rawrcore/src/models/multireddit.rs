@@ -0,0 +1,46 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::{errors::RawrCoreError, util};
+
+// subreddits are listed as [{"name": ...}, ...]; only the names are kept
+fn subreddit_names<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let subreddits = Vec::<Value>::deserialize(deserializer)?;
+
+    Ok(subreddits
+        .iter()
+        .filter_map(|subreddit| subreddit["name"].as_str().map(|name| name.to_string()))
+        .collect())
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Multireddit {
+    pub name: String,
+    pub display_name: String,
+    // e.g. "/user/spez/m/favorites"
+    pub path: String,
+    pub owner: String,
+    #[serde(default)]
+    pub description_md: String,
+    #[serde(default, deserialize_with = "subreddit_names")]
+    pub subreddits: Vec<String>,
+    pub visibility: String,
+    #[serde(default)]
+    pub over_18: bool,
+    pub icon_url: Option<String>,
+    pub created_utc: f64,
+}
+impl Multireddit {
+    // multireddits arrive as [{"kind": "LabeledMulti", "data": {...}}, ...]
+    pub fn list_from_value(value: Value) -> Result<Vec<Self>, RawrCoreError> {
+        #[derive(Deserialize)]
+        struct Envelope {
+            data: Multireddit,
+        }
+
+        Ok(util::from_json_value::<Vec<Envelope>>(value)?
+            .into_iter()
+            .map(|envelope| envelope.data)
+            .collect())
+    }
+}
@@ -0,0 +1,274 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::{fullname::Fullname, Listing};
+use crate::auth::scopes::Scope;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelationshipType {
+    Moderator,
+    // a pending moderator invite
+    ModeratorInvite,
+    // an approved user
+    Contributor,
+    Banned,
+    Muted,
+    WikiBanned,
+    WikiContributor,
+}
+impl RelationshipType {
+    // the `type` sent to /api/friend and /api/unfriend
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationshipType::Moderator => "moderator",
+            RelationshipType::ModeratorInvite => "moderator_invite",
+            RelationshipType::Contributor => "contributor",
+            RelationshipType::Banned => "banned",
+            RelationshipType::Muted => "muted",
+            RelationshipType::WikiBanned => "wikibanned",
+            RelationshipType::WikiContributor => "wikicontributor",
+        }
+    }
+
+    // pending invites have their own endpoint; every other list lives
+    // under /r/{sub}/about
+    pub(crate) fn listing_path(&self, subreddit: &str) -> String {
+        let listing = match self {
+            RelationshipType::Moderator => "moderators",
+            RelationshipType::ModeratorInvite => {
+                return format!("/api/v1/{}/moderators_invited", subreddit)
+            }
+            RelationshipType::Contributor => "contributors",
+            RelationshipType::Banned => "banned",
+            RelationshipType::Muted => "muted",
+            RelationshipType::WikiBanned => "wikibanned",
+            RelationshipType::WikiContributor => "wikicontributors",
+        };
+
+        format!("/r/{}/about/{}", subreddit, listing)
+    }
+
+    // reddit checks the scope of /api/friend and /api/unfriend by `type`
+    pub(crate) fn scope(&self) -> Scope {
+        match self {
+            RelationshipType::Moderator | RelationshipType::ModeratorInvite => Scope::ModOthers,
+            RelationshipType::Contributor | RelationshipType::Banned | RelationshipType::Muted => {
+                Scope::ModContributors
+            }
+            RelationshipType::WikiBanned | RelationshipType::WikiContributor => Scope::ModWiki,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModPermission {
+    All,
+    Access,
+    ChatConfig,
+    ChatOperator,
+    Config,
+    Flair,
+    Mail,
+    Posts,
+    Wiki,
+}
+impl ModPermission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModPermission::All => "all",
+            ModPermission::Access => "access",
+            ModPermission::ChatConfig => "chat_config",
+            ModPermission::ChatOperator => "chat_operator",
+            ModPermission::Config => "config",
+            ModPermission::Flair => "flair",
+            ModPermission::Mail => "mail",
+            ModPermission::Posts => "posts",
+            ModPermission::Wiki => "wiki",
+        }
+    }
+
+    // reddit expects e.g. "-all,+mail,+posts" for a partial set
+    pub(crate) fn permission_string(permissions: &[ModPermission]) -> String {
+        if permissions.contains(&ModPermission::All) {
+            return "+all".to_string();
+        }
+
+        let mut permission_string = "-all".to_string();
+        for permission in permissions {
+            permission_string.push_str(",+");
+            permission_string.push_str(permission.as_str());
+        }
+
+        permission_string
+    }
+}
+
+// an entry of one of a subreddit's user lists. which optional fields are
+// present depends on the list
+#[derive(Deserialize, Clone)]
+pub struct Relationship {
+    pub name: String,
+    pub id: Option<Fullname>,
+    pub rel_id: Option<String>,
+    // when the user was added to the list; pending invites don't say
+    pub date: Option<f64>,
+    pub note: Option<String>,
+    // remaining days of a temporary ban
+    pub days_left: Option<i64>,
+    #[serde(default)]
+    pub mod_permissions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct InvitedModerator {
+    username: String,
+    id: Option<Fullname>,
+    #[serde(rename = "moderatorPermissions", default)]
+    moderator_permissions: HashMap<String, bool>,
+}
+impl From<InvitedModerator> for Relationship {
+    fn from(invite: InvitedModerator) -> Self {
+        let mut mod_permissions: Vec<String> = invite
+            .moderator_permissions
+            .into_iter()
+            .filter(|(_, granted)| *granted)
+            .map(|(permission, _)| permission)
+            .collect();
+        mod_permissions.sort();
+
+        Self {
+            name: invite.username,
+            id: invite.id,
+            rel_id: None,
+            date: None,
+            note: None,
+            days_left: None,
+            mod_permissions,
+        }
+    }
+}
+
+// moderators_invited answers with invites keyed by account fullname, in the
+// order given by `moderatorIds`
+#[derive(Deserialize)]
+struct InvitedModerators {
+    moderators: HashMap<String, InvitedModerator>,
+    #[serde(rename = "moderatorIds", default)]
+    moderator_ids: Vec<String>,
+    #[serde(rename = "allUsersLoaded", default)]
+    all_users_loaded: bool,
+}
+impl InvitedModerators {
+    // the invites and, unless every invite was loaded, the cursor of the next page
+    fn into_parts(mut self) -> (Vec<Relationship>, Option<String>) {
+        let next = match self.all_users_loaded {
+            true => None,
+            false => self.moderator_ids.last().cloned(),
+        };
+
+        let invites = self
+            .moderator_ids
+            .iter()
+            .filter_map(|id| self.moderators.remove(id))
+            .map(Relationship::from)
+            .collect();
+
+        (invites, next)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RelationshipPageShape {
+    Listing(Listing<Relationship>),
+    Invited(InvitedModerators),
+}
+
+// one page of a subreddit user list, in whichever shape the list uses
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub struct RelationshipPage(RelationshipPageShape);
+impl RelationshipPage {
+    pub(crate) fn into_parts(self) -> (Vec<Relationship>, Option<String>) {
+        match self.0 {
+            RelationshipPageShape::Listing(listing) => (listing.children, listing.after),
+            RelationshipPageShape::Invited(invited) => invited.into_parts(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util;
+    use serde_json::json;
+
+    #[test]
+    fn permission_string_lists_partial_permissions() {
+        assert_eq!(ModPermission::permission_string(&[]), "-all");
+        assert_eq!(
+            ModPermission::permission_string(&[ModPermission::Mail, ModPermission::Posts]),
+            "-all,+mail,+posts"
+        );
+    }
+
+    #[test]
+    fn permission_string_collapses_to_all() {
+        assert_eq!(
+            ModPermission::permission_string(&[ModPermission::Wiki, ModPermission::All]),
+            "+all"
+        );
+    }
+
+    #[test]
+    fn invites_are_listed_from_their_own_endpoint() {
+        assert_eq!(
+            RelationshipType::ModeratorInvite.listing_path("rust"),
+            "/api/v1/rust/moderators_invited"
+        );
+        assert_eq!(RelationshipType::Banned.listing_path("rust"), "/r/rust/about/banned");
+    }
+
+    #[test]
+    fn invited_moderators_keep_their_order() {
+        let page: RelationshipPage = util::from_json_value(json!({
+            "moderators": {
+                "t2_b": {"username": "b", "id": "t2_b", "moderatorPermissions": {"all": false, "mail": true}},
+                "t2_a": {"username": "a", "id": "t2_a", "moderatorPermissions": {"all": true}},
+            },
+            "moderatorIds": ["t2_a", "t2_b"],
+            "allUsersLoaded": false,
+            "subredditId": "t5_2qh1i",
+        }))
+        .ok()
+        .unwrap();
+
+        let (invites, next) = page.into_parts();
+        let names: Vec<&str> = invites.iter().map(|invite| invite.name.as_str()).collect();
+
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(invites[1].mod_permissions, ["mail"]);
+        assert_eq!(next.as_deref(), Some("t2_b"));
+    }
+
+    #[test]
+    fn relationship_listings_still_parse() {
+        let page: RelationshipPage = util::from_json_value(json!({
+            "kind": "Listing",
+            "data": {
+                "after": null,
+                "before": null,
+                "dist": null,
+                "children": [{"name": "spez", "id": "t2_1w72", "rel_id": "rb_1", "date": 1.0}],
+            },
+        }))
+        .ok()
+        .unwrap();
+
+        let (users, next) = page.into_parts();
+
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].rel_id.as_deref(), Some("rb_1"));
+        assert_eq!(next, None);
+    }
+}
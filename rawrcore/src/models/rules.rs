@@ -0,0 +1,50 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleKind {
+    // applies to submissions only
+    Link,
+    Comment,
+    All,
+}
+impl RuleKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleKind::Link => "link",
+            RuleKind::Comment => "comment",
+            RuleKind::All => "all",
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Rule {
+    pub short_name: String,
+    pub kind: RuleKind,
+    #[serde(default)]
+    pub description: String,
+    pub description_html: Option<String>,
+    pub violation_reason: Option<String>,
+    pub priority: i64,
+    pub created_utc: f64,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RulesResponse {
+    pub(crate) rules: Vec<Rule>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SubredditRemovalReason {
+    pub id: String,
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RemovalReasonsResponse {
+    pub(crate) data: HashMap<String, SubredditRemovalReason>,
+    pub(crate) order: Vec<String>,
+}
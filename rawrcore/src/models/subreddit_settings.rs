@@ -0,0 +1,354 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{errors::RawrCoreError, util};
+
+use super::fullname::Fullname;
+
+// field names follow /r/{sub}/about/edit; `form_data` maps them to the
+// handful of differently named /api/site_admin parameters. settings without
+// a field of their own are kept in `extra` so they are sent back unchanged
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
+pub struct SubredditSettings {
+    pub subreddit_id: Fullname,
+    pub title: String,
+    #[serde(default)]
+    pub public_description: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub submit_text: String,
+    pub submit_link_label: Option<String>,
+    pub submit_text_label: Option<String>,
+    pub header_hover_text: Option<String>,
+    pub language: String,
+    pub subreddit_type: String,
+    pub content_options: String,
+    #[serde(default)]
+    pub over_18: bool,
+    #[serde(default)]
+    pub allow_images: bool,
+    #[serde(default)]
+    pub allow_videos: bool,
+    #[serde(default)]
+    pub allow_polls: bool,
+    #[serde(default)]
+    pub allow_discovery: bool,
+    #[serde(default)]
+    pub spoilers_enabled: bool,
+    #[serde(default)]
+    pub show_media: bool,
+    #[serde(default)]
+    pub show_media_preview: bool,
+    #[serde(default)]
+    pub collapse_deleted_comments: bool,
+    pub suggested_comment_sort: Option<String>,
+    #[serde(default)]
+    pub comment_score_hide_mins: i64,
+    #[serde(default)]
+    pub exclude_banned_modqueue: bool,
+    pub spam_links: String,
+    pub spam_selfposts: String,
+    pub spam_comments: String,
+    pub wikimode: String,
+    #[serde(default)]
+    pub wiki_edit_karma: i64,
+    #[serde(default)]
+    pub wiki_edit_age: i64,
+    pub key_color: Option<String>,
+    #[serde(default)]
+    pub hide_ads: bool,
+    #[serde(default)]
+    pub restrict_posting: bool,
+    #[serde(default)]
+    pub restrict_commenting: bool,
+    #[serde(default)]
+    pub free_form_reports: bool,
+    #[serde(default)]
+    pub original_content_tag_enabled: bool,
+    #[serde(default)]
+    pub all_original_content: bool,
+    #[serde(default)]
+    pub crowd_control_mode: bool,
+    #[serde(default)]
+    pub crowd_control_level: i64,
+    #[serde(default)]
+    pub welcome_message_enabled: bool,
+    pub welcome_message_text: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettingChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+fn to_object(settings: &SubredditSettings) -> Map<String, Value> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+// InvalidInvocation only carries static messages, so each typed field gets
+// its own
+fn mistyped(field: &str) -> &'static str {
+    match field {
+        "subreddit_id" => "settings patch has a mistyped value for subreddit_id",
+        "title" => "settings patch has a mistyped value for title",
+        "public_description" => "settings patch has a mistyped value for public_description",
+        "description" => "settings patch has a mistyped value for description",
+        "submit_text" => "settings patch has a mistyped value for submit_text",
+        "submit_link_label" => "settings patch has a mistyped value for submit_link_label",
+        "submit_text_label" => "settings patch has a mistyped value for submit_text_label",
+        "header_hover_text" => "settings patch has a mistyped value for header_hover_text",
+        "language" => "settings patch has a mistyped value for language",
+        "subreddit_type" => "settings patch has a mistyped value for subreddit_type",
+        "content_options" => "settings patch has a mistyped value for content_options",
+        "over_18" => "settings patch has a mistyped value for over_18",
+        "allow_images" => "settings patch has a mistyped value for allow_images",
+        "allow_videos" => "settings patch has a mistyped value for allow_videos",
+        "allow_polls" => "settings patch has a mistyped value for allow_polls",
+        "allow_discovery" => "settings patch has a mistyped value for allow_discovery",
+        "spoilers_enabled" => "settings patch has a mistyped value for spoilers_enabled",
+        "show_media" => "settings patch has a mistyped value for show_media",
+        "show_media_preview" => "settings patch has a mistyped value for show_media_preview",
+        "collapse_deleted_comments" => "settings patch has a mistyped value for collapse_deleted_comments",
+        "suggested_comment_sort" => "settings patch has a mistyped value for suggested_comment_sort",
+        "comment_score_hide_mins" => "settings patch has a mistyped value for comment_score_hide_mins",
+        "exclude_banned_modqueue" => "settings patch has a mistyped value for exclude_banned_modqueue",
+        "spam_links" => "settings patch has a mistyped value for spam_links",
+        "spam_selfposts" => "settings patch has a mistyped value for spam_selfposts",
+        "spam_comments" => "settings patch has a mistyped value for spam_comments",
+        "wikimode" => "settings patch has a mistyped value for wikimode",
+        "wiki_edit_karma" => "settings patch has a mistyped value for wiki_edit_karma",
+        "wiki_edit_age" => "settings patch has a mistyped value for wiki_edit_age",
+        "key_color" => "settings patch has a mistyped value for key_color",
+        "hide_ads" => "settings patch has a mistyped value for hide_ads",
+        "restrict_posting" => "settings patch has a mistyped value for restrict_posting",
+        "restrict_commenting" => "settings patch has a mistyped value for restrict_commenting",
+        "free_form_reports" => "settings patch has a mistyped value for free_form_reports",
+        "original_content_tag_enabled" => "settings patch has a mistyped value for original_content_tag_enabled",
+        "all_original_content" => "settings patch has a mistyped value for all_original_content",
+        "crowd_control_mode" => "settings patch has a mistyped value for crowd_control_mode",
+        "crowd_control_level" => "settings patch has a mistyped value for crowd_control_level",
+        "welcome_message_enabled" => "settings patch has a mistyped value for welcome_message_enabled",
+        "welcome_message_text" => "settings patch has a mistyped value for welcome_message_text",
+        _ => "settings patch has a mistyped value",
+    }
+}
+
+fn form_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl SubredditSettings {
+    // about/edit wraps the settings as {"kind": "subreddit_settings", "data": {...}}
+    pub fn from_value(mut value: Value) -> Result<Self, RawrCoreError> {
+        util::from_json_value::<Self>(value["data"].take())
+    }
+
+    // returns a copy with the fields named in `patch` overwritten, so a
+    // partial config (e.g. loaded from a file) can be laid over the current
+    // settings. unknown fields and mistyped values are rejected
+    pub fn patched(&self, patch: &Map<String, Value>) -> Result<Self, RawrCoreError> {
+        let current = to_object(self);
+        let mut merged = current.clone();
+
+        for (field, value) in patch {
+            if !current.contains_key(field) {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "settings patch contains an unknown field",
+                ));
+            }
+
+            // each value is tried on its own so the error can name its field
+            let mut single = current.clone();
+            single.insert(field.clone(), value.clone());
+            if serde_json::from_value::<Self>(Value::Object(single)).is_err() {
+                return Err(RawrCoreError::InvalidInvocation(mistyped(field)));
+            }
+
+            merged.insert(field.clone(), value.clone());
+        }
+
+        util::from_json_value::<Self>(Value::Object(merged))
+    }
+
+    // the fields that differ in `other`, sorted by field name
+    pub fn diff(&self, other: &SubredditSettings) -> Vec<SettingChange> {
+        let old = to_object(self);
+        let mut new = to_object(other);
+
+        let mut changes: Vec<SettingChange> = old
+            .into_iter()
+            .filter_map(|(field, old_value)| {
+                let new_value = new.remove(&field).unwrap_or(Value::Null);
+
+                if old_value.eq(&new_value) {
+                    return None;
+                }
+
+                Some(SettingChange {
+                    field,
+                    old: old_value,
+                    new: new_value,
+                })
+            })
+            .collect();
+
+        // fields only `other` has, e.g. ones added to its `extra`
+        changes.extend(new.into_iter().map(|(field, new_value)| SettingChange {
+            field,
+            old: Value::Null,
+            new: new_value,
+        }));
+        changes.sort_by(|a, b| a.field.cmp(&b.field));
+
+        changes
+    }
+
+    // site_admin resets anything it is not sent, so every field is included
+    pub(crate) fn form_data(&self) -> Vec<(String, String)> {
+        let mut data = vec![("api_type".to_string(), "json".to_string())];
+
+        for (field, value) in to_object(self) {
+            let name = match field.as_str() {
+                "subreddit_id" => "sr".to_string(),
+                "language" => "lang".to_string(),
+                "subreddit_type" => "type".to_string(),
+                "content_options" => "link_type".to_string(),
+                "header_hover_text" => "header-title".to_string(),
+                "default_set" => "allow_top".to_string(),
+                _ => field,
+            };
+
+            data.push((name, form_value(&value)));
+        }
+
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings() -> SubredditSettings {
+        let value = json!({
+            "kind": "subreddit_settings",
+            "data": {
+                "subreddit_id": "t5_2qh1i",
+                "title": "rust",
+                "header_hover_text": "hello",
+                "language": "en",
+                "subreddit_type": "public",
+                "content_options": "any",
+                "spam_links": "high",
+                "spam_selfposts": "high",
+                "spam_comments": "low",
+                "wikimode": "modonly",
+                "default_set": true,
+                "toxicity_threshold_chat_level": 1,
+            },
+        });
+
+        SubredditSettings::from_value(value).ok().unwrap()
+    }
+
+    fn field<'a>(data: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        data.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn unknown_fields_round_trip() {
+        let settings = settings();
+        let data = settings.form_data();
+
+        assert_eq!(settings.extra.get("toxicity_threshold_chat_level"), Some(&json!(1)));
+        assert_eq!(field(&data, "toxicity_threshold_chat_level"), Some("1"));
+    }
+
+    #[test]
+    fn form_data_uses_site_admin_names() {
+        let data = settings().form_data();
+
+        assert_eq!(field(&data, "sr"), Some("t5_2qh1i"));
+        assert_eq!(field(&data, "lang"), Some("en"));
+        assert_eq!(field(&data, "type"), Some("public"));
+        assert_eq!(field(&data, "link_type"), Some("any"));
+        assert_eq!(field(&data, "header-title"), Some("hello"));
+        assert_eq!(field(&data, "allow_top"), Some("true"));
+        assert_eq!(field(&data, "default_set"), None);
+    }
+
+    #[test]
+    fn patch_overwrites_named_fields_including_extra_ones() {
+        let patch = json!({"title": "rustlang", "toxicity_threshold_chat_level": 2});
+        let patched = settings().patched(patch.as_object().unwrap()).ok().unwrap();
+
+        assert_eq!(patched.title, "rustlang");
+        assert_eq!(patched.extra.get("toxicity_threshold_chat_level"), Some(&json!(2)));
+    }
+
+    #[test]
+    fn patch_rejects_unknown_and_mistyped_fields() {
+        let unknown = json!({"not_a_setting": true});
+        let mistyped = json!({"over_18": "yes"});
+
+        assert!(matches!(
+            settings().patched(unknown.as_object().unwrap()),
+            Err(RawrCoreError::InvalidInvocation(_))
+        ));
+        assert!(matches!(
+            settings().patched(mistyped.as_object().unwrap()),
+            Err(RawrCoreError::InvalidInvocation(
+                "settings patch has a mistyped value for over_18"
+            ))
+        ));
+    }
+
+    #[test]
+    fn every_mistyped_field_is_named() {
+        let settings = settings();
+
+        for field in to_object(&settings).keys() {
+            if settings.extra.contains_key(field) {
+                continue;
+            }
+
+            // no typed field takes an array
+            let mut patch = Map::new();
+            patch.insert(field.clone(), json!([]));
+
+            match settings.patched(&patch) {
+                Err(RawrCoreError::InvalidInvocation(message)) => {
+                    assert!(message.ends_with(format!(" for {}", field).as_str()), "{}", message)
+                }
+                _ => panic!("expected {} to be rejected", field),
+            }
+        }
+    }
+
+    #[test]
+    fn diff_lists_changed_fields_in_order() {
+        let current = settings();
+        let mut updated = current.clone();
+        updated.title = "rustlang".to_string();
+        updated.over_18 = true;
+        updated.extra.insert("new_setting".to_string(), json!("on"));
+
+        let fields: Vec<String> = current.diff(&updated).into_iter().map(|change| change.field).collect();
+
+        assert_eq!(fields, ["new_setting", "over_18", "title"]);
+        assert!(current.diff(&current).is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{errors::RawrCoreError, util};
+
+use super::fullname::{Fullname, ThingKind};
+
+// reddit sends `false` for unedited things and the edit timestamp otherwise
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Edited {
+    Never(bool),
+    At(f64),
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Comment {
+    pub id: String,
+    pub name: Fullname,
+    pub author: String,
+    pub author_fullname: Option<Fullname>,
+    pub body: String,
+    pub body_html: Option<String>,
+    pub link_id: Fullname,
+    pub parent_id: Fullname,
+    pub subreddit: String,
+    pub subreddit_id: Fullname,
+    pub permalink: Option<String>,
+    pub score: i64,
+    pub created_utc: f64,
+    pub edited: Option<Edited>,
+    pub distinguished: Option<String>,
+    #[serde(default)]
+    pub stickied: bool,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub is_submitter: bool,
+    #[serde(default)]
+    pub score_hidden: bool,
+    pub depth: Option<i64>,
+    pub approved_by: Option<String>,
+    pub banned_by: Option<Value>,
+    pub num_reports: Option<i64>,
+    #[serde(default)]
+    pub mod_reports: Vec<Value>,
+    #[serde(default)]
+    pub user_reports: Vec<Value>,
+    #[serde(default)]
+    pub replies: Value,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Account {
+    pub id: String,
+    pub name: String,
+    pub created_utc: Option<f64>,
+    #[serde(default)]
+    pub link_karma: i64,
+    #[serde(default)]
+    pub comment_karma: i64,
+    pub total_karma: Option<i64>,
+    #[serde(default)]
+    pub is_mod: bool,
+    #[serde(default)]
+    pub is_gold: bool,
+    #[serde(default)]
+    pub is_employee: bool,
+    pub has_verified_email: Option<bool>,
+    pub icon_img: Option<String>,
+    pub is_suspended: Option<bool>,
+    pub subreddit: Option<Value>,
+}
+impl Account {
+    pub fn fullname(&self) -> Result<Fullname, RawrCoreError> {
+        Fullname::new(ThingKind::Account, self.id.as_str())
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Link {
+    pub id: String,
+    pub name: Fullname,
+    pub title: String,
+    pub author: String,
+    pub author_fullname: Option<Fullname>,
+    pub subreddit: String,
+    pub subreddit_id: Fullname,
+    #[serde(default)]
+    pub selftext: String,
+    pub selftext_html: Option<String>,
+    pub url: Option<String>,
+    pub domain: Option<String>,
+    pub permalink: String,
+    pub score: i64,
+    pub upvote_ratio: Option<f64>,
+    #[serde(default)]
+    pub num_comments: i64,
+    pub created_utc: f64,
+    pub edited: Option<Edited>,
+    #[serde(default)]
+    pub is_self: bool,
+    #[serde(default)]
+    pub over_18: bool,
+    #[serde(default)]
+    pub spoiler: bool,
+    #[serde(default)]
+    pub stickied: bool,
+    #[serde(default)]
+    pub locked: bool,
+    pub distinguished: Option<String>,
+    pub link_flair_text: Option<String>,
+    pub link_flair_template_id: Option<String>,
+    pub removed_by_category: Option<String>,
+    pub approved_by: Option<String>,
+    pub banned_by: Option<Value>,
+    pub num_reports: Option<i64>,
+    #[serde(default)]
+    pub mod_reports: Vec<Value>,
+    #[serde(default)]
+    pub user_reports: Vec<Value>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Message {
+    pub id: String,
+    pub name: Fullname,
+    pub author: Option<String>,
+    pub dest: String,
+    pub subject: String,
+    pub body: String,
+    pub body_html: Option<String>,
+    pub created_utc: f64,
+    #[serde(default)]
+    pub new: bool,
+    #[serde(default)]
+    pub was_comment: bool,
+    pub parent_id: Option<Fullname>,
+    pub first_message_name: Option<Fullname>,
+    pub context: Option<String>,
+    pub subreddit: Option<String>,
+    pub distinguished: Option<String>,
+    #[serde(default)]
+    pub replies: Value,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Subreddit {
+    pub id: String,
+    pub name: Fullname,
+    pub display_name: String,
+    pub display_name_prefixed: Option<String>,
+    pub title: Option<String>,
+    pub public_description: Option<String>,
+    pub description: Option<String>,
+    pub subscribers: Option<i64>,
+    pub over18: Option<bool>,
+    pub subreddit_type: Option<String>,
+    pub created_utc: Option<f64>,
+    pub url: String,
+    pub quarantine: Option<bool>,
+    pub user_is_moderator: Option<bool>,
+    pub user_is_banned: Option<bool>,
+    pub user_is_subscriber: Option<bool>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Award {
+    pub id: Option<String>,
+    pub award_id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub icon_70: Option<String>,
+    pub icon_40: Option<String>,
+    pub url: Option<String>,
+    pub granted_at: Option<f64>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "kind", content = "data")]
+pub enum Thing {
+    #[serde(rename = "t1")]
+    Comment(Box<Comment>),
+    #[serde(rename = "t2")]
+    Account(Box<Account>),
+    #[serde(rename = "t3")]
+    Link(Box<Link>),
+    #[serde(rename = "t4")]
+    Message(Box<Message>),
+    #[serde(rename = "t5")]
+    Subreddit(Box<Subreddit>),
+    #[serde(rename = "t6")]
+    Award(Box<Award>),
+}
+impl Thing {
+    pub fn from_value(value: Value) -> Result<Self, RawrCoreError> {
+        util::from_json_value::<Thing>(value)
+    }
+
+    pub fn kind(&self) -> ThingKind {
+        match self {
+            Thing::Comment(_) => ThingKind::Comment,
+            Thing::Account(_) => ThingKind::Account,
+            Thing::Link(_) => ThingKind::Link,
+            Thing::Message(_) => ThingKind::Message,
+            Thing::Subreddit(_) => ThingKind::Subreddit,
+            Thing::Award(_) => ThingKind::Award,
+        }
+    }
+
+    // awards carry no fullname of their own
+    pub fn fullname(&self) -> Option<Fullname> {
+        match self {
+            Thing::Comment(c) => Some(c.name.clone()),
+            Thing::Account(a) => a.fullname().ok(),
+            Thing::Link(l) => Some(l.name.clone()),
+            Thing::Message(m) => Some(m.name.clone()),
+            Thing::Subreddit(s) => Some(s.name.clone()),
+            Thing::Award(_) => None,
+        }
+    }
+}
+
+// inbox listings return comment replies and mentions as t1 things shaped
+// like messages (no link_id or subreddit_id), so both kinds are read as
+// `Message` and told apart by variant
+#[derive(Deserialize, Clone)]
+#[serde(tag = "kind", content = "data")]
+pub enum InboxItem {
+    #[serde(rename = "t1")]
+    Comment(Box<Message>),
+    #[serde(rename = "t4")]
+    Message(Box<Message>),
+}
+impl InboxItem {
+    pub fn message(&self) -> &Message {
+        match self {
+            InboxItem::Comment(m) | InboxItem::Message(m) => m,
+        }
+    }
+
+    pub fn fullname(&self) -> &Fullname {
+        &self.message().name
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::{errors::RawrCoreError, util};
+
+// accounts are embedded as full t2 things; only the name is kept
+fn account_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value = Option::<Value>::deserialize(deserializer)?;
+
+    Ok(value.and_then(|account| account["data"]["name"].as_str().map(|name| name.to_string())))
+}
+
+fn account_names<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let accounts = Vec::<Value>::deserialize(deserializer)?;
+
+    Ok(accounts
+        .iter()
+        .filter_map(|account| account["data"]["name"].as_str().map(|name| name.to_string()))
+        .collect())
+}
+
+#[derive(Deserialize, Clone)]
+pub struct WikiPage {
+    #[serde(default)]
+    pub content_md: String,
+    pub content_html: Option<String>,
+    #[serde(default)]
+    pub may_revise: bool,
+    pub reason: Option<String>,
+    pub revision_date: Option<f64>,
+    #[serde(default, deserialize_with = "account_name")]
+    pub revision_by: Option<String>,
+    pub revision_id: Option<String>,
+}
+impl WikiPage {
+    // pages arrive wrapped as {"kind": "wikipage", "data": {...}}
+    pub fn from_value(mut value: Value) -> Result<Self, RawrCoreError> {
+        util::from_json_value::<Self>(value["data"].take())
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct WikiRevision {
+    pub id: String,
+    pub page: String,
+    pub timestamp: f64,
+    pub reason: Option<String>,
+    #[serde(default, deserialize_with = "account_name")]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub revision_hidden: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WikiPermLevel {
+    // falls back to the subreddit's wiki settings
+    Inherit,
+    ApprovedEditors,
+    ModsOnly,
+}
+impl WikiPermLevel {
+    pub fn as_level(&self) -> u8 {
+        match self {
+            WikiPermLevel::Inherit => 0,
+            WikiPermLevel::ApprovedEditors => 1,
+            WikiPermLevel::ModsOnly => 2,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WikiPermLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(WikiPermLevel::ApprovedEditors),
+            2 => Ok(WikiPermLevel::ModsOnly),
+            _ => Ok(WikiPermLevel::Inherit),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct WikiPageSettings {
+    pub permlevel: WikiPermLevel,
+    pub listed: bool,
+    #[serde(default, deserialize_with = "account_names")]
+    pub editors: Vec<String>,
+}
+impl WikiPageSettings {
+    // settings arrive wrapped as {"kind": "wikipagesettings", "data": {...}}
+    pub fn from_value(mut value: Value) -> Result<Self, RawrCoreError> {
+        util::from_json_value::<Self>(value["data"].take())
+    }
+}
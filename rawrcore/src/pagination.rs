@@ -0,0 +1,554 @@
+use futures::{stream, Stream};
+use serde::de::DeserializeOwned;
+use std::{collections::VecDeque, marker::PhantomData};
+
+use crate::{
+    errors::RawrCoreError,
+    models::{
+        flair::{FlairListResponse, UserFlair},
+        mod_action::ModAction,
+        mod_note::{ModNote, ModNotesResponse},
+        modmail::{Conversation, ConversationsResponse},
+        relationship::{Relationship, RelationshipPage},
+        things::{InboxItem, Thing},
+        wiki::WikiRevision,
+        Listing,
+    },
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+// the shape of one page of a paginated endpoint. most endpoints answer with
+// listings, but a few, like flairlist and mod notes, page their own way
+pub trait Page<T>: DeserializeOwned {
+    // the query parameter the cursor is sent back in
+    const CURSOR_PARAM: &'static str = "after";
+    const MAX_PAGE_SIZE: usize = 100;
+    // listings also take the number of items seen so far as `count`
+    const SENDS_COUNT: bool = false;
+
+    // the items of the page and the cursor of the next one, if any
+    fn into_parts(self) -> (Vec<T>, Option<String>);
+
+    // the cursor that resumes right after `item`, when the item carries one
+    fn item_cursor(item: &T) -> Option<String>;
+}
+
+// listing children that can be resumed after, by fullname or listing id
+pub trait ListingItem {
+    fn listing_cursor(&self) -> Option<String>;
+}
+
+impl ListingItem for Thing {
+    fn listing_cursor(&self) -> Option<String> {
+        self.fullname().map(|fullname| fullname.to_string())
+    }
+}
+
+impl ListingItem for InboxItem {
+    fn listing_cursor(&self) -> Option<String> {
+        Some(self.fullname().to_string())
+    }
+}
+
+impl ListingItem for ModAction {
+    fn listing_cursor(&self) -> Option<String> {
+        Some(self.id.clone())
+    }
+}
+
+impl ListingItem for Relationship {
+    fn listing_cursor(&self) -> Option<String> {
+        self.rel_id.clone()
+    }
+}
+
+impl ListingItem for WikiRevision {
+    fn listing_cursor(&self) -> Option<String> {
+        Some(format!("WikiRevision_{}", self.id))
+    }
+}
+
+impl<T: DeserializeOwned + ListingItem> Page<T> for Listing<T> {
+    const SENDS_COUNT: bool = true;
+
+    fn into_parts(self) -> (Vec<T>, Option<String>) {
+        (self.children, self.after)
+    }
+
+    fn item_cursor(item: &T) -> Option<String> {
+        item.listing_cursor()
+    }
+}
+
+// subreddit user lists, including pending moderator invites, which are
+// resumed after the invited account
+impl Page<Relationship> for RelationshipPage {
+    const SENDS_COUNT: bool = true;
+
+    fn into_parts(self) -> (Vec<Relationship>, Option<String>) {
+        RelationshipPage::into_parts(self)
+    }
+
+    fn item_cursor(item: &Relationship) -> Option<String> {
+        match &item.rel_id {
+            Some(rel_id) => Some(rel_id.clone()),
+            None => item.id.as_ref().map(|id| id.to_string()),
+        }
+    }
+}
+
+// flairlist entries carry no id, so a paginator stopped part way through a
+// page resumes from the start of that page
+impl Page<UserFlair> for FlairListResponse {
+    const MAX_PAGE_SIZE: usize = 1000;
+
+    fn into_parts(self) -> (Vec<UserFlair>, Option<String>) {
+        (self.users, self.next)
+    }
+
+    fn item_cursor(_: &UserFlair) -> Option<String> {
+        None
+    }
+}
+
+// modmail pages after the id of the last conversation
+impl Page<Conversation> for ConversationsResponse {
+    fn into_parts(self) -> (Vec<Conversation>, Option<String>) {
+        let conversations = self.into_conversations();
+        let next = conversations
+            .last()
+            .map(|conversation| conversation.id.clone());
+
+        (conversations, next)
+    }
+
+    fn item_cursor(item: &Conversation) -> Option<String> {
+        Some(item.id.clone())
+    }
+}
+
+// mod notes page backwards in time with a `before` cursor
+impl Page<ModNote> for ModNotesResponse {
+    const CURSOR_PARAM: &'static str = "before";
+
+    fn into_parts(self) -> (Vec<ModNote>, Option<String>) {
+        match self.has_next_page {
+            true => (self.mod_notes, self.end_cursor),
+            false => (self.mod_notes, None),
+        }
+    }
+
+    fn item_cursor(item: &ModNote) -> Option<String> {
+        item.cursor.clone()
+    }
+}
+
+pub struct ListingPaginator<'s, R, T = Thing, P = Listing<T>>
+where
+    R: RetryStrategy,
+    P: Page<T>,
+{
+    session: &'s mut Session<R>,
+    path: String,
+    params: Vec<(String, String)>,
+    limit: Option<usize>,
+    // the cursor of the next page to fetch
+    after: Option<String>,
+    // where to resume while items of the current page are still buffered
+    resume: Option<String>,
+    yielded: usize,
+    buffer: VecDeque<T>,
+    exhausted: bool,
+    page: PhantomData<P>,
+}
+
+impl<'s, R, T, P> ListingPaginator<'s, R, T, P>
+where
+    R: RetryStrategy,
+    P: Page<T>,
+{
+    pub fn new(
+        session: &'s mut Session<R>,
+        path: &str,
+        params: Option<Vec<(String, String)>>,
+        limit: Option<usize>,
+        after: Option<String>,
+    ) -> Self {
+        Self {
+            session,
+            path: path.to_string(),
+            params: params.unwrap_or_default(),
+            limit,
+            resume: after.clone(),
+            after,
+            yielded: 0,
+            buffer: VecDeque::new(),
+            exhausted: false,
+            page: PhantomData,
+        }
+    }
+
+    // where the last yielded item left off; pass it back into `new` to
+    // resume without skipping anything that was fetched but not yet yielded
+    pub fn cursor(&self) -> Option<&str> {
+        match self.buffer.is_empty() {
+            true => self.after.as_deref(),
+            false => self.resume.as_deref(),
+        }
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        self.limit.map(|limit| limit.saturating_sub(self.yielded))
+    }
+
+    async fn fetch_page(&mut self) -> Result<(), RawrCoreError> {
+        let page_size = match self.remaining() {
+            Some(remaining) => remaining.min(P::MAX_PAGE_SIZE),
+            None => P::MAX_PAGE_SIZE,
+        };
+
+        let mut params = self.params.clone();
+        params.push(("limit".to_string(), page_size.to_string()));
+
+        if P::SENDS_COUNT {
+            params.push(("count".to_string(), self.yielded.to_string()));
+        }
+
+        if let Some(after) = &self.after {
+            params.push((P::CURSOR_PARAM.to_string(), after.clone()));
+        }
+
+        let value = self
+            .session
+            .request::<(), (), (), Vec<(String, String)>>(
+                RequestMethod::Get,
+                self.path.as_str(),
+                None,
+                None,
+                None,
+                Some(&params),
+                None,
+            )
+            .await?;
+        let (items, next) = util::from_json_value::<P>(value)?.into_parts();
+
+        if next.is_none() || items.is_empty() {
+            self.exhausted = true;
+        }
+
+        self.resume = self.after.take();
+        self.after = next;
+        self.buffer.extend(items);
+
+        Ok(())
+    }
+
+    pub async fn next_item(&mut self) -> Option<Result<T, RawrCoreError>> {
+        if self.remaining() == Some(0) {
+            return None;
+        }
+
+        if self.buffer.is_empty() && !self.exhausted {
+            if let Err(e) = self.fetch_page().await {
+                self.exhausted = true;
+                return Some(Err(e));
+            }
+        }
+
+        let item = self.buffer.pop_front()?;
+        self.yielded += 1;
+
+        if let Some(cursor) = P::item_cursor(&item) {
+            self.resume = Some(cursor);
+        }
+
+        Some(Ok(item))
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = Result<T, RawrCoreError>> + 's
+    where
+        T: 's,
+        P: 's,
+    {
+        stream::unfold(self, |mut paginator| async move {
+            paginator.next_item().await.map(|item| (item, paginator))
+        })
+    }
+
+    // must not be called from inside an async runtime; use `into_stream` there
+    pub fn into_blocking_iter(self) -> Result<BlockingListingIter<'s, R, T, P>, RawrCoreError> {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(rt) => rt,
+            Err(_) => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "could not start a runtime for blocking pagination",
+                ))
+            }
+        };
+
+        Ok(BlockingListingIter {
+            paginator: self,
+            runtime,
+        })
+    }
+}
+
+pub struct BlockingListingIter<'s, R, T = Thing, P = Listing<T>>
+where
+    R: RetryStrategy,
+    P: Page<T>,
+{
+    paginator: ListingPaginator<'s, R, T, P>,
+    runtime: tokio::runtime::Runtime,
+}
+impl<'s, R, T, P> BlockingListingIter<'s, R, T, P>
+where
+    R: RetryStrategy,
+    P: Page<T>,
+{
+    pub fn cursor(&self) -> Option<&str> {
+        self.paginator.cursor()
+    }
+}
+
+impl<'s, R, T, P> Iterator for BlockingListingIter<'s, R, T, P>
+where
+    R: RetryStrategy,
+    P: Page<T>,
+{
+    type Item = Result<T, RawrCoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.paginator.next_item())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sessions::FiniteRetryStrategy,
+        test_util::{self, MockServer},
+    };
+    use serde_json::{json, Value};
+
+    fn next<T, P: Page<T>>(
+        paginator: &mut ListingPaginator<'_, FiniteRetryStrategy, T, P>,
+    ) -> Option<T> {
+        match test_util::runtime().block_on(paginator.next_item()) {
+            Some(Ok(item)) => Some(item),
+            Some(Err(_)) => panic!("expected the page to load"),
+            None => None,
+        }
+    }
+
+    fn revisions(ids: &[&str], after: Option<&str>) -> Value {
+        let children: Vec<Value> = ids
+            .iter()
+            .map(|id| json!({"id": id, "page": "index", "timestamp": 1.0}))
+            .collect();
+
+        json!({
+            "kind": "Listing",
+            "data": {"after": after, "before": null, "dist": ids.len(), "children": children},
+        })
+    }
+
+    // two pages of revisions, split after "c"
+    fn revision_server() -> MockServer {
+        MockServer::start(|request| match request.param("after") {
+            None => (200, revisions(&["a", "b", "c"], Some("WikiRevision_c"))),
+            Some("WikiRevision_c") => (200, revisions(&["d"], None)),
+            Some(_) => (404, Value::Null),
+        })
+    }
+
+    #[test]
+    fn cursor_points_at_last_yielded_listing_item() {
+        let server = revision_server();
+        let mut session = server.session();
+        let mut paginator = ListingPaginator::<_, WikiRevision>::new(
+            &mut session,
+            "/r/rust/wiki/revisions",
+            None,
+            None,
+            None,
+        );
+
+        assert_eq!(paginator.cursor(), None);
+
+        next(&mut paginator);
+        assert_eq!(paginator.cursor(), Some("WikiRevision_a"));
+
+        next(&mut paginator);
+        next(&mut paginator);
+        assert_eq!(paginator.cursor(), Some("WikiRevision_c"));
+
+        assert_eq!(
+            next(&mut paginator).map(|revision| revision.id),
+            Some("d".to_string())
+        );
+        assert!(next(&mut paginator).is_none());
+    }
+
+    #[test]
+    fn listing_pages_send_cursor_count_and_limit() {
+        let server = revision_server();
+        let mut session = server.session();
+        let paginator = ListingPaginator::<_, WikiRevision>::new(
+            &mut session,
+            "/r/rust/wiki/revisions",
+            None,
+            None,
+            None,
+        );
+
+        let ids: Vec<String> = paginator
+            .into_blocking_iter()
+            .ok()
+            .unwrap()
+            .map(|revision| revision.ok().unwrap().id)
+            .collect();
+        assert_eq!(ids, ["a", "b", "c", "d"]);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].param("limit"), Some("100"));
+        assert_eq!(requests[0].param("count"), Some("0"));
+        assert_eq!(requests[1].param("after"), Some("WikiRevision_c"));
+        assert_eq!(requests[1].param("count"), Some("3"));
+    }
+
+    #[test]
+    fn empty_pages_end_pagination() {
+        let server = MockServer::start(|request| match request.param("after") {
+            None => (200, revisions(&[], Some("WikiRevision_z"))),
+            Some(_) => (404, Value::Null),
+        });
+        let mut session = server.session();
+        let mut paginator = ListingPaginator::<_, WikiRevision>::new(
+            &mut session,
+            "/r/rust/wiki/revisions",
+            None,
+            None,
+            None,
+        );
+
+        assert!(next(&mut paginator).is_none());
+        assert!(next(&mut paginator).is_none());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn limit_caps_page_size_and_items() {
+        let server = revision_server();
+        let mut session = server.session();
+        let mut paginator = ListingPaginator::<_, WikiRevision>::new(
+            &mut session,
+            "/r/rust/wiki/revisions",
+            None,
+            Some(2),
+            None,
+        );
+
+        assert!(next(&mut paginator).is_some());
+        assert!(next(&mut paginator).is_some());
+        assert!(next(&mut paginator).is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].param("limit"), Some("2"));
+    }
+
+    #[test]
+    fn cursor_falls_back_to_page_start_without_item_cursors() {
+        let server = MockServer::start(|_| {
+            (
+                200,
+                json!({
+                    "users": [
+                        {"user": "a", "flair_text": "one", "flair_css_class": null},
+                        {"user": "b", "flair_text": "two", "flair_css_class": null},
+                    ],
+                    "next": "t2_b",
+                }),
+            )
+        });
+        let mut session = server.session();
+        let mut paginator = ListingPaginator::<_, UserFlair, FlairListResponse>::new(
+            &mut session,
+            "/r/rust/api/flairlist",
+            None,
+            None,
+            Some("t2_start".to_string()),
+        );
+
+        next(&mut paginator);
+        assert_eq!(paginator.cursor(), Some("t2_start"));
+
+        next(&mut paginator);
+        assert_eq!(paginator.cursor(), Some("t2_b"));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("after"), Some("t2_start"));
+        assert_eq!(requests[0].param("limit"), Some("1000"));
+        assert_eq!(requests[0].param("count"), None);
+    }
+
+    #[test]
+    fn mod_notes_without_next_page_have_no_next_cursor() {
+        let page: ModNotesResponse = util::from_json_value(json!({
+            "mod_notes": [],
+            "end_cursor": "MTY2",
+            "has_next_page": false,
+        }))
+        .ok()
+        .unwrap();
+
+        let (notes, next) = page.into_parts();
+
+        assert!(notes.is_empty());
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn conversation_pages_follow_conversation_ids() {
+        let conversation = |id: &str| {
+            json!({
+                "id": id,
+                "subject": "hi",
+                "state": 0,
+                "owner": {"id": "t5_2qh1i", "displayName": "rust", "type": "subreddit"},
+                "objIds": [{"id": format!("m{}", id), "key": "messages"}],
+            })
+        };
+        let message = |id: &str| {
+            json!({
+                "id": format!("m{}", id),
+                "body": "<p>hi</p>",
+                "bodyMarkdown": "hi",
+                "author": {"name": "spez"},
+                "date": "2024-01-01T00:00:00+00:00",
+            })
+        };
+
+        let page: ConversationsResponse = util::from_json_value(json!({
+            "conversations": {"a": conversation("a"), "b": conversation("b")},
+            "conversationIds": ["b", "a"],
+            "messages": {"ma": message("a"), "mb": message("b")},
+        }))
+        .ok()
+        .unwrap();
+
+        let (conversations, next) = page.into_parts();
+        let ids: Vec<&str> = conversations.iter().map(|c| c.id.as_str()).collect();
+
+        assert_eq!(ids, ["b", "a"]);
+        assert_eq!(conversations[0].messages.len(), 1);
+        assert_eq!(next.as_deref(), Some("a"));
+    }
+}
@@ -1,10 +1,8 @@
 use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
 use reqwest::{Response, header::HeaderMap};
-use serde::Serialize;
 use serde_json::Value;
 use std::{
     cmp::{max_by, min_by},
-    collections::HashMap,
     time::Duration as StdDuration,
 };
 
@@ -25,20 +23,23 @@ impl RateLimiter {
         }
     }
 
-    pub fn delay(&self) {
-        if self.next_request_timestamp.is_none() {
-            return;
-        }
-
-        let sleep_seconds =
-            self.next_request_timestamp.unwrap().timestamp() - Utc::now().timestamp();
+    // how long to wait before the next request may be sent
+    pub fn delay_duration(&self) -> Option<StdDuration> {
+        let sleep_seconds = self.next_request_timestamp?.timestamp() - Utc::now().timestamp();
 
         if sleep_seconds <= 0 {
-            return;
+            return None;
         }
 
-        println!("Sleeping: {} seconds prior to call", sleep_seconds);
-        std::thread::sleep(StdDuration::from_secs(sleep_seconds as u64))
+        Some(StdDuration::from_secs(sleep_seconds as u64))
+    }
+
+    // blocks the current thread; async callers should sleep for
+    // `delay_duration` instead
+    pub fn delay(&self) {
+        if let Some(delay) = self.delay_duration() {
+            std::thread::sleep(delay)
+        }
     }
 
     pub fn update(&mut self, response_headers: reqwest::header::HeaderMap) {
@@ -67,7 +68,7 @@ impl RateLimiter {
         let ratelimit_reset = response_headers
             .get("x-ratelimit-reset")
             .expect("no x-ratelimit-reset header present");
-        let ratelimit_remaining = response_headers
+        let _ratelimit_remaining = response_headers
             .get("x-ratelimit-remaining")
             .expect("no x-ratelimit-remaining header present");
         let ratelimit_used = response_headers
@@ -85,7 +86,7 @@ impl RateLimiter {
             .parse::<u32>()
             .expect("could not parse x-ratelimit-used to i64");
 
-        self.remaining = Some(ChronoDuration::seconds(seconds_to_reset.into()));
+        self.remaining = Some(ChronoDuration::seconds(seconds_to_reset));
         self.used = Some(used);
         self.reset_timestamp =
             Utc::now().checked_add_signed(ChronoDuration::seconds(seconds_to_reset));
@@ -96,7 +97,6 @@ impl RateLimiter {
         }
 
         let reset_timestamp_seconds = self.reset_timestamp.unwrap().timestamp();
-        let next_request_timestamp_seconds = self.next_request_timestamp.unwrap().timestamp();
         let remaining = self.remaining.unwrap().num_seconds();
 
         let nrt = min_by(
@@ -113,7 +113,7 @@ impl RateLimiter {
         self.next_request_timestamp = Some(Utc.timestamp_opt(nrt, 0).unwrap());
     }
 
-    pub fn call<R, H>(&self, request_function: R, set_header_callback: H, data: Value) -> Response
+    pub fn call<R, H>(&mut self, request_function: R, set_header_callback: H, data: Value) -> Response
     where
         R: Fn(Value, HeaderMap) -> Response,
         H: Fn() -> HeaderMap,
@@ -125,6 +125,34 @@ impl RateLimiter {
 
         self.update(response.headers().to_owned());
 
-        return response;
+        response
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_duration_without_limit_is_none() {
+        assert!(RateLimiter::new().delay_duration().is_none());
+    }
+
+    #[test]
+    fn delay_duration_waits_until_next_request() {
+        let mut rate_limiter = RateLimiter::new();
+        rate_limiter.next_request_timestamp = Utc::now().checked_add_signed(ChronoDuration::seconds(5));
+
+        let delay = rate_limiter.delay_duration().unwrap();
+        assert!(delay <= StdDuration::from_secs(5) && delay >= StdDuration::from_secs(4));
+
+        rate_limiter.next_request_timestamp = Utc::now().checked_sub_signed(ChronoDuration::seconds(5));
+        assert!(rate_limiter.delay_duration().is_none());
     }
 }
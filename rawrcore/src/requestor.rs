@@ -1,30 +1,33 @@
-use crate::{errors::RawrCoreError, sessions::Session};
+use crate::errors::{RawrCoreError, RequestErrorData};
 
 pub struct Requestor {
-    http: Session,
+    http: reqwest::Client,
+    pub user_agent: String,
     pub oauth_url: String,
     pub reddit_url: String,
     pub timeout: f32,
 }
 
 impl Requestor {
-    pub fn new<'r>(
+    pub fn new(
         user_agent: &str,
         oauth_url: Option<&str>,
         reddit_url: Option<&str>,
-        session: Option<Session>,
+        http: Option<reqwest::Client>,
         timeout: Option<f32>,
-    ) -> Result<Self, RawrCoreError<'r>> {
-        if user_agent.eq("") || user_agent.len() < 7 {
+    ) -> Result<Self, RawrCoreError> {
+        if user_agent.is_empty() || user_agent.len() < 7 {
             return Err(RawrCoreError::InvalidInvocation("user_agent is not descriptive"));
         }
 
         Ok(Self {
-            http: match session {
-                Some(sess) => sess,
-                None => Session {},
+            http: match http {
+                Some(client) => client,
+                None => reqwest::Client::new(),
             },
 
+            user_agent: user_agent.to_string(),
+
             oauth_url: match oauth_url {
                 Some(url) => url.to_string(),
                 None => "https://oauth.reddit.com".to_string(),
@@ -44,7 +47,44 @@ impl Requestor {
 
     pub fn close(&self) {}
 
-    pub fn request(&self, timeout: Option<f32>) -> reqwest::Response {
+    pub async fn request(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        data: &[(&str, &str)],
+        auth: (String, String),
+        timeout: Option<f32>,
+    ) -> Result<reqwest::Response, RawrCoreError> {
+        let timeout = match timeout {
+            Some(to) => to,
+            None => self.timeout,
+        };
+
+        let request = match self
+            .http
+            .request(method, url)
+            .basic_auth(auth.0, Some(auth.1))
+            .header(reqwest::header::USER_AGENT, self.user_agent.as_str())
+            .timeout(std::time::Duration::from_secs_f32(timeout))
+            .form(data)
+            .build()
+        {
+            Ok(r) => r,
+            Err(e) => {
+                return Err(RawrCoreError::RequestError(RequestErrorData {
+                    original_error: e,
+                    request: None,
+                }))
+            }
+        };
+        let request_copy = request.try_clone();
 
+        match self.http.execute(request).await {
+            Ok(response) => Ok(response),
+            Err(e) => Err(RawrCoreError::RequestError(RequestErrorData {
+                original_error: e,
+                request: request_copy,
+            })),
+        }
     }
 }
\ No newline at end of file
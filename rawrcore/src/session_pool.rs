@@ -0,0 +1,162 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    auth::authorizers::Authorizer,
+    errors::RawrCoreError,
+    rate_limit::RateLimiter,
+    sessions::{FiniteRetryStrategy, RequestMethod, Session},
+};
+
+pub struct SessionPool {
+    sessions: Vec<(String, Session<FiniteRetryStrategy>)>,
+    shared_rate_limiter: Option<Arc<Mutex<RateLimiter>>>,
+    next_read_only: usize,
+}
+
+impl SessionPool {
+    pub fn new(share_rate_limiter: bool) -> Self {
+        Self {
+            sessions: vec![],
+            shared_rate_limiter: match share_rate_limiter {
+                true => Some(Arc::new(Mutex::new(RateLimiter::new()))),
+                false => None,
+            },
+            next_read_only: 0,
+        }
+    }
+
+    // `rate_limiter` lets accounts share a limiter outside of the pool's own,
+    // e.g. with sessions the pool doesn't manage. without one the account
+    // uses the pool's shared limiter, or a limiter of its own when the pool
+    // doesn't share one
+    pub fn add_account(
+        &mut self,
+        name: &str,
+        authorizer: Box<dyn Authorizer>,
+        rate_limiter: Option<Arc<Mutex<RateLimiter>>>,
+    ) -> Result<(), RawrCoreError> {
+        if self.sessions.iter().any(|(n, _)| n.eq(name)) {
+            return Err(RawrCoreError::InvalidInvocation(
+                "an account with that name is already in the pool",
+            ));
+        }
+
+        let rate_limiter = rate_limiter.or_else(|| self.shared_rate_limiter.clone());
+        let session = Session::new(authorizer, rate_limiter);
+        self.sessions.push((name.to_string(), session));
+
+        Ok(())
+    }
+
+    pub fn remove_account(&mut self, name: &str) -> Option<Session<FiniteRetryStrategy>> {
+        let index = self.sessions.iter().position(|(n, _)| n.eq(name))?;
+
+        if self.next_read_only > index {
+            self.next_read_only -= 1;
+        }
+
+        Some(self.sessions.remove(index).1)
+    }
+
+    pub fn account_names(&self) -> Vec<&str> {
+        self.sessions.iter().map(|(n, _)| n.as_str()).collect()
+    }
+
+    pub fn account(&mut self, name: &str) -> Result<&mut Session<FiniteRetryStrategy>, RawrCoreError> {
+        match self.sessions.iter_mut().find(|(n, _)| n.eq(name)) {
+            Some((_, session)) => Ok(session),
+            None => Err(RawrCoreError::InvalidInvocation(
+                "no account with that name in the pool",
+            )),
+        }
+    }
+
+    pub fn next_read_only_account(&mut self) -> Result<&mut Session<FiniteRetryStrategy>, RawrCoreError> {
+        if self.sessions.is_empty() {
+            return Err(RawrCoreError::InvalidInvocation("session pool has no accounts"));
+        }
+
+        let index = self.next_read_only % self.sessions.len();
+        self.next_read_only = (index + 1) % self.sessions.len();
+
+        Ok(&mut self.sessions[index].1)
+    }
+
+    pub async fn read_only_request<P: Serialize + ?Sized>(
+        &mut self,
+        path: &str,
+        params: Option<&P>,
+        timeout: Option<f32>,
+    ) -> Result<Value, RawrCoreError> {
+        self.next_read_only_account()?
+            .request::<(), (), (), P>(RequestMethod::Get, path, None, None, None, params, timeout)
+            .await
+    }
+
+    pub async fn refresh_account(&mut self, name: &str) -> Result<(), RawrCoreError> {
+        self.account(name)?.authorizer_mut().refresh().await
+    }
+
+    // each account is refreshed on its own so one bad credential doesn't
+    // block the rest of the pool; failures are returned by account name
+    pub async fn refresh_expired(&mut self) -> Vec<(String, RawrCoreError)> {
+        let mut failures = vec![];
+
+        for (name, session) in self.sessions.iter_mut() {
+            if session.authorizer().is_valid() {
+                continue;
+            }
+
+            if let Err(e) = session.authorizer_mut().refresh().await {
+                failures.push((name.clone(), e));
+            }
+        }
+
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::authorizer;
+
+    #[test]
+    fn accounts_use_the_limiter_they_are_given() {
+        let limiter = Arc::new(Mutex::new(RateLimiter::new()));
+        let mut pool = SessionPool::new(true);
+
+        assert!(pool.add_account("a", authorizer(None), Some(limiter.clone())).is_ok());
+        assert!(pool.add_account("b", authorizer(None), None).is_ok());
+
+        assert_eq!(Arc::strong_count(&limiter), 2);
+        assert_eq!(Arc::strong_count(pool.shared_rate_limiter.as_ref().unwrap()), 2);
+    }
+
+    #[test]
+    fn duplicate_account_names_are_rejected() {
+        let mut pool = SessionPool::new(false);
+
+        assert!(pool.add_account("a", authorizer(None), None).is_ok());
+        assert!(matches!(
+            pool.add_account("a", authorizer(None), None),
+            Err(RawrCoreError::InvalidInvocation(_))
+        ));
+    }
+
+    #[test]
+    fn read_only_accounts_rotate() {
+        let mut pool = SessionPool::new(false);
+        assert!(pool.add_account("a", authorizer(None), None).is_ok());
+        assert!(pool.add_account("b", authorizer(None), None).is_ok());
+
+        let first: *const Session<FiniteRetryStrategy> = pool.next_read_only_account().ok().unwrap();
+        let second: *const Session<FiniteRetryStrategy> = pool.next_read_only_account().ok().unwrap();
+        let third: *const Session<FiniteRetryStrategy> = pool.next_read_only_account().ok().unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(first, third);
+    }
+}
@@ -1,12 +1,28 @@
 use crate::{
-    auth::authorizers::{Authorizer, BaseAuthorizer},
-    errors::{RawrCoreError, RedirectData, ResponseErrorData, SpecialErrorData}, rate_limit::RateLimiter,
+    auth::{
+        authorizers::Authorizer,
+        scopes::{self, Scope},
+    },
+    constants,
+    errors::{
+        BadJSONData, ConflictData, InsufficientScopeData, RawrCoreError, RedirectData, RequestErrorData,
+        ResponseErrorData, SpecialErrorData,
+    },
+    models::Identity,
+    rate_limit::RateLimiter,
+    upload::{self, UploadFile, UploadFiles},
+    util,
 };
 use lazy_static::lazy_static;
 use rand::Rng;
-use reqwest::{header::HeaderValue, Client, Request, Response, StatusCode};
+use reqwest::{header::USER_AGENT, Method, Response, StatusCode};
 use serde::Serialize;
-use std::{collections::HashMap, thread, time::Duration};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 lazy_static! {
     static ref RETRY_STATUSES: [u16; 7] = [
@@ -21,113 +37,117 @@ lazy_static! {
 }
 
 fn is_success_status(response: &Response) -> bool {
-    match response.status() {
-        StatusCode::ACCEPTED => true,
-        StatusCode::CREATED => true,
-        StatusCode::OK => true,
-        _ => false,
-    }
+    matches!(
+        response.status(),
+        StatusCode::ACCEPTED | StatusCode::CREATED | StatusCode::OK
+    )
 }
 
-fn handle_redirect(response: &Response) -> Option<RawrCoreError> {
-    let location = response.headers().get("location");
-    let mut path = "";
+// some endpoints, such as read_all_messages, answer 202 with nothing in the
+// body; those are returned as null rather than as `BadJSON`
+fn parse_body(body: String) -> Result<Value, RawrCoreError> {
+    if body.trim().is_empty() {
+        return Ok(Value::Null);
+    }
 
-    if location.is_some() {
-        let loc = location.unwrap();
-        let val = match loc.to_str() {
-            Ok(v) => v,
-            Err(_) => "",
-        };
+    match serde_json::from_str::<Value>(body.as_str()) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(RawrCoreError::BadJSON(BadJSONData {
+            body,
+            original_error: e,
+        })),
+    }
+}
 
-        path = val;
+fn is_insufficient_scope(response: &Response) -> bool {
+    match response.headers().get("www-authenticate") {
+        Some(header) => header.to_str().unwrap_or_default().contains("insufficient_scope"),
+        None => false,
     }
+}
+
+fn handle_redirect(response: Response) -> RawrCoreError {
+    let path = match response.headers().get("location") {
+        Some(loc) => loc.to_str().unwrap_or_default().to_string(),
+        None => "".to_string(),
+    };
 
-    Some(RawrCoreError::Redirect(RedirectData {
-        path: path.to_string(),
-        response: response,
-    }))
+    RawrCoreError::Redirect(RedirectData {
+        path,
+        response,
+    })
 }
 
-fn response_to_rawrcore_error(response: &Response) -> Option<RawrCoreError> {
-    let response_error_data = ResponseErrorData { response: response };
+fn response_to_rawrcore_error(response: Response) -> Result<Response, RawrCoreError> {
+    let status = response.status();
+
+    let err: RawrCoreError = match status {
+        StatusCode::INTERNAL_SERVER_ERROR => RawrCoreError::ServerError(ResponseErrorData { response }),
+
+        StatusCode::BAD_REQUEST => RawrCoreError::BadRequest(ResponseErrorData { response }),
 
-    let err: Option<RawrCoreError> = match response.status() {
-        StatusCode::INTERNAL_SERVER_ERROR => Some(RawrCoreError::ServerError(response_error_data)),
+        StatusCode::CONFLICT => RawrCoreError::Conflict(ConflictData {
+            response: Some(response),
+            edit: None,
+        }),
 
-        StatusCode::BAD_REQUEST => Some(RawrCoreError::BadRequest(response_error_data)),
+        StatusCode::UNAUTHORIZED => RawrCoreError::InvalidToken(ResponseErrorData { response }),
 
-        StatusCode::CONFLICT => Some(RawrCoreError::Conflict(response_error_data)),
+        StatusCode::FORBIDDEN => RawrCoreError::Forbidden(ResponseErrorData { response }),
 
         StatusCode::FOUND => handle_redirect(response),
 
-        StatusCode::GATEWAY_TIMEOUT => Some(RawrCoreError::ServerError(response_error_data)),
-        StatusCode::INTERNAL_SERVER_ERROR => Some(RawrCoreError::ServerError(response_error_data)),
+        StatusCode::GATEWAY_TIMEOUT => RawrCoreError::ServerError(ResponseErrorData { response }),
         StatusCode::UNSUPPORTED_MEDIA_TYPE => {
-            let mut try_after: u16 = 0;
-            let try_after_header = response.headers().get("x-try-after");
-
-            if try_after_header.is_some() {
-                try_after = match try_after_header {
-                    Some(ta) => {
-                        let ta_string = match ta.to_str() {
-                            Ok(s) => s.to_string(),
-                            Err(e) => "0".to_string(),
-                        };
-
-                        match ta_string.parse::<u16>() {
-                            Ok(r) => r,
-                            Err(_) => 0,
-                        }
-                    }
-
-                    None => 0,
-                }
-            }
+            let try_after: u16 = match response.headers().get("x-try-after") {
+                Some(ta) => ta.to_str().unwrap_or("0").parse::<u16>().unwrap_or(0),
+                None => 0,
+            };
 
             let special_err_data = SpecialErrorData {
-                response: response,
+                response,
                 message: "".to_string(),
                 retry_after: try_after,
             };
 
-            Some(RawrCoreError::SpecialError(special_err_data))
+            RawrCoreError::SpecialError(special_err_data)
         }
         StatusCode::MOVED_PERMANENTLY => handle_redirect(response),
-        StatusCode::NOT_FOUND => Some(RawrCoreError::NotFound(response_error_data)),
+        StatusCode::NOT_FOUND => RawrCoreError::NotFound(ResponseErrorData { response }),
         StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE => {
-            Some(RawrCoreError::TooLarge(response_error_data))
-        }
-        StatusCode::URI_TOO_LONG => Some(RawrCoreError::URITooLong(response_error_data)),
-        StatusCode::SERVICE_UNAVAILABLE => Some(RawrCoreError::ServerError(response_error_data)),
-        StatusCode::TOO_MANY_REQUESTS => Some(RawrCoreError::TooManyRequests(response_error_data)),
-        StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS => Some(
-            RawrCoreError::UnavailableForLegalReasons(response_error_data),
-        ),
+            RawrCoreError::TooLarge(ResponseErrorData { response })
+        }
+        StatusCode::URI_TOO_LONG => RawrCoreError::URITooLong(ResponseErrorData { response }),
+        StatusCode::SERVICE_UNAVAILABLE => RawrCoreError::ServerError(ResponseErrorData { response }),
+        StatusCode::TOO_MANY_REQUESTS => RawrCoreError::TooManyRequests(ResponseErrorData { response }),
+        StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS => {
+            RawrCoreError::UnavailableForLegalReasons(ResponseErrorData { response })
+        }
         code => match code.as_u16() {
-            520 => Some(RawrCoreError::ServerError(response_error_data)),
-            522 => Some(RawrCoreError::ServerError(response_error_data)),
-            _ => None,
+            520 => RawrCoreError::ServerError(ResponseErrorData { response }),
+            522 => RawrCoreError::ServerError(ResponseErrorData { response }),
+            _ => return Ok(response),
         },
     };
 
-    err
+    Err(err)
 }
-trait RetryStrategy {
-    fn sleep(&self) -> ();
+pub trait RetryStrategy: Clone {
+    // how long to wait before the next attempt, if at all
+    fn sleep_duration(&self) -> Option<Duration>;
+    fn consume_available_retry(&self) -> Self;
+    fn should_return_on_failure(&self) -> bool;
 }
 
-struct FiniteRetryStrategy {
+#[derive(Clone)]
+pub struct FiniteRetryStrategy {
     retries: u8,
 }
 
 impl FiniteRetryStrategy {
     fn new(retries: Option<u8>) -> Self {
         Self {
-            retries: match retries {
-                Some(r) => r,
-                None => 3,
-            },
+            retries: retries.unwrap_or(3),
         }
     }
 
@@ -142,6 +162,12 @@ impl FiniteRetryStrategy {
 
         None
     }
+}
+
+impl RetryStrategy for FiniteRetryStrategy {
+    fn sleep_duration(&self) -> Option<Duration> {
+        self.sleep_seconds().map(Duration::from_secs_f32)
+    }
 
     fn consume_available_retry(&self) -> Self {
         Self {
@@ -158,54 +184,465 @@ impl FiniteRetryStrategy {
     }
 }
 
-impl RetryStrategy for FiniteRetryStrategy {
-    fn sleep(&self) {
-        let sleep_seconds = self.sleep_seconds();
-
-        match sleep_seconds {
-            Some(secs) => thread::sleep(Duration::from_secs_f32(secs)),
-            None => (),
-        }
-    }
-}
-
+#[derive(Clone, Copy)]
 pub enum RequestMethod {
     Post,
     Get,
     Patch,
+    Put,
     Delete,
 }
-pub struct Session<R, A> where R: RetryStrategy, A: Authorizer {
+impl RequestMethod {
+    fn as_reqwest_method(&self) -> Method {
+        match self {
+            RequestMethod::Post => Method::POST,
+            RequestMethod::Get => Method::GET,
+            RequestMethod::Patch => Method::PATCH,
+            RequestMethod::Put => Method::PUT,
+            RequestMethod::Delete => Method::DELETE,
+        }
+    }
+}
+
+pub struct Session<R> where R: RetryStrategy {
     http: reqwest::Client,
-    rate_limiter: RateLimiter,
+    rate_limiter: Arc<Mutex<RateLimiter>>,
     retry_strategy_t: R,
-    authorizer: A
+    authorizer: Box<dyn Authorizer>,
+    check_scopes: bool,
+    identity: Option<Identity>
 }
 
-impl <'r, R, A> Session<R, A> where R: RetryStrategy, A: Authorizer {
-    pub fn new(authorizer: Option<A>) -> Self {        
+impl Session<FiniteRetryStrategy> {
+    pub fn new(authorizer: Box<dyn Authorizer>, rate_limiter: Option<Arc<Mutex<RateLimiter>>>) -> Self {
         Self {
             authorizer,
             http: reqwest::Client::new(),
-            rate_limiter: RateLimiter::new(),
-            retry_strategy_t: FiniteRetryStrategy::new(None)
+            rate_limiter: match rate_limiter {
+                Some(rl) => rl,
+                None => Arc::new(Mutex::new(RateLimiter::new())),
+            },
+            retry_strategy_t: FiniteRetryStrategy::new(None),
+            check_scopes: false,
+            identity: None
         }
     }
-    pub fn  request<
+}
+
+impl <R> Session<R> where R: RetryStrategy {
+    pub fn authorizer(&self) -> &dyn Authorizer {
+        self.authorizer.as_ref()
+    }
+
+    pub fn authorizer_mut(&mut self) -> &mut dyn Authorizer {
+        self.authorizer.as_mut()
+    }
+
+    pub fn set_authorizer(&mut self, authorizer: Box<dyn Authorizer>) {
+        self.authorizer = authorizer;
+        self.identity = None;
+    }
+
+    pub fn set_check_scopes(&mut self, check_scopes: bool) {
+        self.check_scopes = check_scopes;
+    }
+
+    pub async fn identity(&mut self) -> Result<&Identity, RawrCoreError> {
+        if self.identity.is_none() {
+            return self.refresh_identity().await;
+        }
+
+        Ok(self.identity.as_ref().unwrap())
+    }
+
+    pub async fn refresh_identity(&mut self) -> Result<&Identity, RawrCoreError> {
+        let value = self
+            .request::<(), (), (), ()>(
+                RequestMethod::Get,
+                constants::ME_PATH.as_str(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await?;
+
+        Ok(self.identity.insert(util::from_json_value::<Identity>(value)?))
+    }
+
+    pub async fn is_authenticated_as(&mut self, username: &str) -> Result<bool, RawrCoreError> {
+        let identity = self.identity().await?;
+
+        Ok(identity.name.eq_ignore_ascii_case(username))
+    }
+
+    fn granted_scopes(&self) -> HashSet<String> {
+        match self.authorizer.scopes() {
+            Some(granted) => granted.clone(),
+            None => HashSet::new(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn request<
         D: Serialize + ?Sized,
-        F: Serialize + ?Sized,
+        F: UploadFiles + ?Sized,
         J: Serialize + ?Sized,
         P: Serialize + ?Sized,
     >(
-        &self,
+        &mut self,
+        method: RequestMethod,
+        path: &str,
+        data: Option<&D>,
+        files: Option<&F>,
+        json: Option<&J>,
+        params: Option<&P>,
+        timeout: Option<f32>
+    ) -> Result<Value, RawrCoreError> {
+        let check_scopes = self.check_scopes;
+
+        self.send(scopes::required_scope(path), check_scopes, method, path, data, files, json, params, timeout)
+            .await
+    }
+
+    // like `request`, but `scope` is checked before sending whether or not
+    // `check_scopes` is set. used where the scope reddit wants depends on
+    // more than the path
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn request_with_scope<
+        D: Serialize + ?Sized,
+        F: UploadFiles + ?Sized,
+        J: Serialize + ?Sized,
+        P: Serialize + ?Sized,
+    >(
+        &mut self,
+        scope: Scope,
+        method: RequestMethod,
+        path: &str,
+        data: Option<&D>,
+        files: Option<&F>,
+        json: Option<&J>,
+        params: Option<&P>,
+        timeout: Option<f32>
+    ) -> Result<Value, RawrCoreError> {
+        self.send(Some(scope), true, method, path, data, files, json, params, timeout).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn send<
+        D: Serialize + ?Sized,
+        F: UploadFiles + ?Sized,
+        J: Serialize + ?Sized,
+        P: Serialize + ?Sized,
+    >(
+        &mut self,
+        required_scope: Option<Scope>,
+        check_scope: bool,
         method: RequestMethod,
-        path: &'r str,
-        data: Option<&'r D>,
-        files: Option<&'r F>,
-        json: Option<&'r J>,
-        params: Option<&'r P>,
+        path: &str,
+        data: Option<&D>,
+        files: Option<&F>,
+        json: Option<&J>,
+        params: Option<&P>,
         timeout: Option<f32>
-    ) {
-        
+    ) -> Result<Value, RawrCoreError> {
+        if !self.authorizer.is_valid() {
+            self.authorizer.refresh().await?;
+        }
+
+        if check_scope && self.authorizer.scopes().is_some() {
+            if let Some(required) = required_scope {
+                let granted = self.granted_scopes();
+
+                if !required.is_granted(&granted) {
+                    return Err(RawrCoreError::InsufficientScope(InsufficientScopeData {
+                        required: Some(required),
+                        granted,
+                        response: None,
+                    }));
+                }
+            }
+        }
+
+        let requestor = self.authorizer.get_base().get_requestor();
+        let url = format!("{}{}", requestor.oauth_url, path);
+        let user_agent = requestor.user_agent.clone();
+        let timeout = match timeout {
+            Some(to) => to,
+            None => requestor.timeout,
+        };
+
+        let files = files.map(|files| files.files()).unwrap_or_default();
+        let mut retry_strategy = self.retry_strategy_t.clone();
+
+        loop {
+            if let Some(wait) = retry_strategy.sleep_duration() {
+                tokio::time::sleep(wait).await;
+            }
+
+            // the limiter may be shared between sessions, so the lock is
+            // released before waiting
+            let delay = self
+                .rate_limiter
+                .lock()
+                .expect("rate limiter lock poisoned")
+                .delay_duration();
+
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+            }
+
+            let mut builder = self
+                .http
+                .request(method.as_reqwest_method(), url.as_str())
+                .bearer_auth(self.authorizer.access_token()?)
+                .header(USER_AGENT, user_agent.as_str())
+                .timeout(Duration::from_secs_f32(timeout))
+                .query(&[("raw_json", "1")]);
+
+            if let Some(params) = params {
+                builder = builder.query(params);
+            }
+
+            if !files.is_empty() {
+                builder = builder.multipart(upload::multipart_form(data, &files)?);
+            } else if let Some(data) = data {
+                builder = builder.form(data);
+            }
+
+            if let Some(json) = json {
+                builder = builder.json(json);
+            }
+
+            let request = match builder.build() {
+                Ok(r) => r,
+                Err(e) => {
+                    return Err(RawrCoreError::RequestError(RequestErrorData {
+                        original_error: e,
+                        request: None,
+                    }))
+                }
+            };
+            let request_copy = request.try_clone();
+
+            let response = match self.http.execute(request).await {
+                Ok(r) => r,
+                Err(e) => {
+                    if (e.is_timeout() || e.is_connect()) && retry_strategy.should_return_on_failure() {
+                        retry_strategy = retry_strategy.consume_available_retry();
+                        continue;
+                    }
+
+                    return Err(RawrCoreError::RequestError(RequestErrorData {
+                        original_error: e,
+                        request: request_copy,
+                    }));
+                }
+            };
+
+            self.rate_limiter
+                .lock()
+                .expect("rate limiter lock poisoned")
+                .update(response.headers().to_owned());
+
+            if RETRY_STATUSES.contains(&response.status().as_u16())
+                && retry_strategy.should_return_on_failure()
+            {
+                retry_strategy = retry_strategy.consume_available_retry();
+                continue;
+            }
+
+            if response.status() == StatusCode::NO_CONTENT {
+                return Ok(Value::Null);
+            }
+
+            if response.status() == StatusCode::FORBIDDEN && is_insufficient_scope(&response) {
+                return Err(RawrCoreError::InsufficientScope(InsufficientScopeData {
+                    required: required_scope,
+                    granted: self.granted_scopes(),
+                    response: Some(response),
+                }));
+            }
+
+            let response = response_to_rawrcore_error(response)?;
+
+            if !is_success_status(&response) {
+                return Err(RawrCoreError::ResponseError(ResponseErrorData { response }));
+            }
+
+            let body = match response.text().await {
+                Ok(b) => b,
+                Err(e) => {
+                    return Err(RawrCoreError::RequestError(RequestErrorData {
+                        original_error: e,
+                        request: None,
+                    }))
+                }
+            };
+
+            return parse_body(body);
+        }
+    }
+
+    // posts a multipart form to a url outside of the oauth api, such as a
+    // media upload lease; no bearer token is sent and the rate limiter is
+    // left alone since these hosts do not count against reddit's limits
+    pub async fn upload<D: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        data: Option<&D>,
+        file: &UploadFile,
+        timeout: Option<f32>,
+    ) -> Result<Response, RawrCoreError> {
+        let requestor = self.authorizer.get_base().get_requestor();
+        let timeout = match timeout {
+            Some(to) => to,
+            None => requestor.timeout,
+        };
+
+        let response = match self
+            .http
+            .post(url)
+            .header(USER_AGENT, requestor.user_agent.as_str())
+            .timeout(Duration::from_secs_f32(timeout))
+            .multipart(upload::multipart_form(data, &[file])?)
+            .send()
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                return Err(RawrCoreError::RequestError(RequestErrorData {
+                    original_error: e,
+                    request: None,
+                }))
+            }
+        };
+
+        if !is_success_status(&response) && response.status() != StatusCode::NO_CONTENT {
+            return Err(RawrCoreError::ResponseError(ResponseErrorData { response }));
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{authorizer, runtime, MockServer};
+    use serde_json::json;
+
+    // answers /api/v1/me with a new name on every request so refetches show
+    fn identity_server() -> MockServer {
+        let mut count = 0;
+        MockServer::start(move |_| {
+            count += 1;
+            (
+                200,
+                json!({"id": "abc", "name": format!("Someone{}", count), "link_karma": 1, "comment_karma": 2}),
+            )
+        })
+    }
+
+    #[test]
+    fn parse_body_treats_empty_body_as_null() {
+        assert!(matches!(parse_body(String::new()), Ok(Value::Null)));
+        assert!(matches!(parse_body(" \n".to_string()), Ok(Value::Null)));
+    }
+
+    #[test]
+    fn parse_body_parses_json() {
+        match parse_body(r#"{"json": {"errors": []}}"#.to_string()) {
+            Ok(value) => assert!(value["json"]["errors"].is_array()),
+            Err(_) => panic!("expected a json body to parse"),
+        }
+    }
+
+    #[test]
+    fn parse_body_rejects_malformed_json() {
+        match parse_body("<html>".to_string()) {
+            Err(RawrCoreError::BadJSON(data)) => assert_eq!(data.body, "<html>"),
+            _ => panic!("expected BadJSON"),
+        }
+    }
+
+    #[test]
+    fn identity_is_fetched_once_and_cached() {
+        let server = identity_server();
+        let mut session = server.session();
+
+        runtime().block_on(async {
+            assert_eq!(session.identity().await.ok().unwrap().name, "Someone1");
+            assert_eq!(session.identity().await.ok().unwrap().name, "Someone1");
+        });
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, constants::ME_PATH.as_str());
+    }
+
+    #[test]
+    fn refresh_identity_refetches() {
+        let server = identity_server();
+        let mut session = server.session();
+
+        runtime().block_on(async {
+            assert_eq!(session.identity().await.ok().unwrap().name, "Someone1");
+            assert_eq!(session.refresh_identity().await.ok().unwrap().name, "Someone2");
+            assert_eq!(session.identity().await.ok().unwrap().name, "Someone2");
+        });
+
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn set_authorizer_clears_identity() {
+        let server = identity_server();
+        let mut session = server.session();
+
+        runtime().block_on(async {
+            assert_eq!(session.identity().await.ok().unwrap().name, "Someone1");
+            session.set_authorizer(authorizer(Some(server.url.as_str())));
+            assert_eq!(session.identity().await.ok().unwrap().name, "Someone2");
+        });
+
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn is_authenticated_as_ignores_case() {
+        let server = identity_server();
+        let mut session = server.session();
+
+        runtime().block_on(async {
+            assert!(session.is_authenticated_as("someone1").await.ok().unwrap());
+            assert!(session.is_authenticated_as("SOMEONE1").await.ok().unwrap());
+            assert!(!session.is_authenticated_as("someone2").await.ok().unwrap());
+        });
+
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn expiry_follows_the_token_response() {
+        let server = identity_server();
+        let mut session = server.session();
+        assert!(session.authorizer().expires_at().is_none());
+        assert!(session.authorizer().scopes().is_none());
+
+        let before = chrono::Utc::now();
+        runtime().block_on(async {
+            session.identity().await.ok().unwrap();
+        });
+        let after = chrono::Utc::now();
+
+        // the mock server grants a one hour token with every scope, and the
+        // authorizer keeps ten seconds of slack before it
+        let expires_at = session.authorizer().expires_at().unwrap();
+        assert!(expires_at >= before + chrono::Duration::seconds(3590));
+        assert!(expires_at <= after + chrono::Duration::seconds(3590));
+        assert!(session.authorizer().scopes().unwrap().contains("*"));
     }
 }
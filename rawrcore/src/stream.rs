@@ -0,0 +1,459 @@
+use futures::{stream, Stream};
+use rand::Rng;
+use std::{
+    collections::{HashSet, VecDeque},
+    marker::PhantomData,
+    time::Duration,
+};
+
+use crate::{
+    errors::RawrCoreError,
+    models::{
+        mod_action::ModAction,
+        modmail::Conversation,
+        things::{InboxItem, Thing},
+        Listing,
+    },
+    pagination::Page,
+    sessions::{RequestMethod, RetryStrategy, Session},
+    util,
+};
+
+const FETCH_LIMIT: usize = 100;
+const SEEN_CAPACITY: usize = 301;
+const MAX_WAIT_SECONDS: f32 = 16.0;
+
+pub trait Streamable {
+    fn stream_key(&self) -> Option<String>;
+}
+
+impl Streamable for Thing {
+    fn stream_key(&self) -> Option<String> {
+        self.fullname().map(|fullname| fullname.to_string())
+    }
+}
+
+impl Streamable for InboxItem {
+    fn stream_key(&self) -> Option<String> {
+        Some(self.fullname().to_string())
+    }
+}
+
+impl Streamable for ModAction {
+    fn stream_key(&self) -> Option<String> {
+        Some(self.id.clone())
+    }
+}
+
+impl Streamable for Conversation {
+    fn stream_key(&self) -> Option<String> {
+        Some(self.id.clone())
+    }
+}
+
+pub(crate) struct BoundedSet {
+    capacity: usize,
+    order: VecDeque<String>,
+    items: HashSet<String>,
+}
+impl BoundedSet {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::new(),
+            items: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn contains(&self, item: &str) -> bool {
+        self.items.contains(item)
+    }
+
+    pub(crate) fn insert(&mut self, item: String) {
+        if self.items.contains(&item) {
+            return;
+        }
+
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.items.remove(&oldest);
+            }
+        }
+
+        self.order.push_back(item.clone());
+        self.items.insert(item);
+    }
+}
+
+pub(crate) struct ExponentialBackoff {
+    base: f32,
+    max: f32,
+}
+impl ExponentialBackoff {
+    pub(crate) fn new(max: f32) -> Self {
+        Self { base: 1.0, max }
+    }
+
+    // returns the next wait with up to +/- 1/16th jitter, doubling the base
+    // each call until it reaches the maximum
+    pub(crate) fn next_wait(&mut self) -> Duration {
+        let max_jitter = self.base / 16.0;
+        let jitter = rand::thread_rng().gen_range(-max_jitter..=max_jitter);
+        let wait = (self.base + jitter).min(self.max);
+
+        self.base = (self.base * 2.0).min(self.max);
+
+        Duration::from_secs_f32(wait)
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.base = 1.0;
+    }
+}
+
+pub enum StreamSource {
+    SubredditSubmissions(String),
+    SubredditComments(String),
+    RedditorSubmissions(String),
+    RedditorComments(String),
+    ModQueue(String),
+}
+impl StreamSource {
+    pub fn path(&self) -> String {
+        match self {
+            StreamSource::SubredditSubmissions(sub) => format!("/r/{}/new", sub),
+            StreamSource::SubredditComments(sub) => format!("/r/{}/comments", sub),
+            StreamSource::RedditorSubmissions(name) => format!("/user/{}/submitted", name),
+            StreamSource::RedditorComments(name) => format!("/user/{}/comments", name),
+            StreamSource::ModQueue(sub) => format!("/r/{}/about/modqueue", sub),
+        }
+    }
+}
+
+// polls the first page of an endpoint forever, yielding unseen items oldest
+// first. `Ok(None)` is yielded as a pause signal once `pause_after`
+// consecutive polls have come back without anything new (after every empty
+// poll when 0). pages are listings unless `P` says otherwise
+pub struct ItemStream<'s, R, T = Thing, P = Listing<T>>
+where
+    R: RetryStrategy,
+    T: Streamable,
+    P: Page<T>,
+{
+    session: &'s mut Session<R>,
+    path: String,
+    params: Vec<(String, String)>,
+    seen: BoundedSet,
+    backoff: ExponentialBackoff,
+    buffer: VecDeque<T>,
+    skip_existing: bool,
+    pause_after: Option<usize>,
+    empty_responses: usize,
+    wait: Option<Duration>,
+    page: PhantomData<P>,
+}
+
+impl<'s, R> ItemStream<'s, R, Thing>
+where
+    R: RetryStrategy,
+{
+    pub fn from_source(
+        session: &'s mut Session<R>,
+        source: StreamSource,
+        skip_existing: bool,
+        pause_after: Option<usize>,
+    ) -> Self {
+        Self::new(
+            session,
+            source.path().as_str(),
+            None,
+            skip_existing,
+            pause_after,
+        )
+    }
+}
+
+impl<'s, R, T, P> ItemStream<'s, R, T, P>
+where
+    R: RetryStrategy,
+    T: Streamable,
+    P: Page<T>,
+{
+    pub fn new(
+        session: &'s mut Session<R>,
+        path: &str,
+        params: Option<Vec<(String, String)>>,
+        skip_existing: bool,
+        pause_after: Option<usize>,
+    ) -> Self {
+        Self {
+            session,
+            path: path.to_string(),
+            params: params.unwrap_or_default(),
+            seen: BoundedSet::new(SEEN_CAPACITY),
+            backoff: ExponentialBackoff::new(MAX_WAIT_SECONDS),
+            buffer: VecDeque::new(),
+            skip_existing,
+            pause_after,
+            empty_responses: 0,
+            wait: None,
+            page: PhantomData,
+        }
+    }
+
+    async fn poll(&mut self) -> Result<Vec<T>, RawrCoreError> {
+        if let Some(wait) = self.wait.take() {
+            tokio::time::sleep(wait).await;
+        }
+
+        let mut params = self.params.clone();
+        params.push(("limit".to_string(), FETCH_LIMIT.to_string()));
+
+        let value = self
+            .session
+            .request::<(), (), (), Vec<(String, String)>>(
+                RequestMethod::Get,
+                self.path.as_str(),
+                None,
+                None,
+                None,
+                Some(&params),
+                None,
+            )
+            .await?;
+        let (items, _) = util::from_json_value::<P>(value)?.into_parts();
+
+        let mut found = vec![];
+
+        // pages are newest first
+        for item in items.into_iter().rev() {
+            let key = match item.stream_key() {
+                Some(key) => key,
+                None => continue,
+            };
+
+            if self.seen.contains(key.as_str()) {
+                continue;
+            }
+
+            self.seen.insert(key);
+            found.push(item);
+        }
+
+        Ok(found)
+    }
+
+    pub async fn next_item(&mut self) -> Result<Option<T>, RawrCoreError> {
+        loop {
+            if let Some(item) = self.buffer.pop_front() {
+                return Ok(Some(item));
+            }
+
+            let found = match self.poll().await {
+                Ok(found) => found,
+                Err(e) => {
+                    self.wait = Some(self.backoff.next_wait());
+                    return Err(e);
+                }
+            };
+
+            if self.skip_existing {
+                self.skip_existing = false;
+                continue;
+            }
+
+            if found.is_empty() {
+                self.empty_responses += 1;
+                self.wait = Some(self.backoff.next_wait());
+
+                if self.pause_after.is_some_and(|pause_after| self.empty_responses > pause_after) {
+                    self.empty_responses = 0;
+                    return Ok(None);
+                }
+
+                continue;
+            }
+
+            self.empty_responses = 0;
+            self.backoff.reset();
+            self.buffer.extend(found);
+        }
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = Result<Option<T>, RawrCoreError>> + 's
+    where
+        T: 's,
+        P: 's,
+    {
+        stream::unfold(self, |mut item_stream| async move {
+            let item = item_stream.next_item().await;
+            Some((item, item_stream))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sessions::FiniteRetryStrategy,
+        test_util::{self, MockServer},
+    };
+    use serde_json::{json, Value};
+
+    fn link(id: &str) -> Value {
+        json!({"kind": "t3", "data": {
+            "id": id,
+            "name": format!("t3_{}", id),
+            "title": id,
+            "author": "spez",
+            "subreddit": "rust",
+            "subreddit_id": "t5_2qh1i",
+            "permalink": format!("/r/rust/comments/{}/", id),
+            "score": 1,
+            "created_utc": 1.0,
+        }})
+    }
+
+    // newest first, like reddit
+    fn page(ids: &[&str]) -> Value {
+        let children: Vec<Value> = ids.iter().map(|id| link(id)).collect();
+
+        json!({
+            "kind": "Listing",
+            "data": {"after": null, "before": null, "dist": ids.len(), "children": children},
+        })
+    }
+
+    // answers each poll with the next of `pages`, then with empty pages
+    fn server(pages: Vec<Vec<&'static str>>) -> MockServer {
+        let mut pages = VecDeque::from(pages);
+
+        MockServer::start(move |_| (200, page(&pages.pop_front().unwrap_or_default())))
+    }
+
+    fn stream(
+        session: &mut Session<FiniteRetryStrategy>,
+        skip_existing: bool,
+        pause_after: Option<usize>,
+    ) -> ItemStream<'_, FiniteRetryStrategy> {
+        let source = StreamSource::SubredditSubmissions("rust".to_string());
+        let mut stream = ItemStream::from_source(session, source, skip_existing, pause_after);
+        // keeps the empty polls from sleeping
+        stream.backoff = ExponentialBackoff::new(0.0);
+
+        stream
+    }
+
+    // the id of the next item, or None for a pause
+    fn next(stream: &mut ItemStream<'_, FiniteRetryStrategy>) -> Option<String> {
+        match test_util::runtime().block_on(stream.next_item()) {
+            Ok(Some(Thing::Link(link))) => Some(link.id),
+            Ok(Some(_)) => panic!("expected a link"),
+            Ok(None) => None,
+            Err(_) => panic!("expected the poll to succeed"),
+        }
+    }
+
+    #[test]
+    fn bounded_set_forgets_the_oldest_item() {
+        let mut set = BoundedSet::new(2);
+        set.insert("a".to_string());
+        set.insert("b".to_string());
+        set.insert("a".to_string());
+        set.insert("c".to_string());
+
+        assert!(!set.contains("a"));
+        assert!(set.contains("b") && set.contains("c"));
+    }
+
+    #[test]
+    fn seen_items_hold_a_full_page_and_then_some() {
+        let mut set = BoundedSet::new(SEEN_CAPACITY);
+        for n in 0..=SEEN_CAPACITY {
+            set.insert(n.to_string());
+        }
+
+        assert!(!set.contains("0"));
+        assert!(set.contains("1"));
+        assert!(set.contains(SEEN_CAPACITY.to_string().as_str()));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap_and_resets() {
+        let mut backoff = ExponentialBackoff::new(MAX_WAIT_SECONDS);
+
+        for expected in [1.0, 2.0, 4.0, 8.0, 16.0, 16.0] {
+            let wait = backoff.next_wait().as_secs_f32();
+            assert!((wait - expected).abs() <= expected / 16.0 + f32::EPSILON);
+            assert!(wait <= MAX_WAIT_SECONDS);
+        }
+
+        backoff.reset();
+        assert!(backoff.next_wait().as_secs_f32() <= 1.0 + 1.0 / 16.0);
+    }
+
+    #[test]
+    fn new_items_are_yielded_oldest_first() {
+        let server = server(vec![vec!["c", "b", "a"], vec!["d", "c", "b"]]);
+        let mut session = server.session();
+        let mut stream = stream(&mut session, false, Some(0));
+
+        assert_eq!(next(&mut stream).as_deref(), Some("a"));
+        assert_eq!(next(&mut stream).as_deref(), Some("b"));
+        assert_eq!(next(&mut stream).as_deref(), Some("c"));
+        assert_eq!(next(&mut stream).as_deref(), Some("d"));
+        assert_eq!(next(&mut stream), None);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/r/rust/new");
+        assert_eq!(requests[0].param("limit"), Some("100"));
+    }
+
+    #[test]
+    fn skip_existing_drops_the_first_poll() {
+        let server = server(vec![vec!["b", "a"], vec!["c", "b", "a"]]);
+        let mut session = server.session();
+        let mut stream = stream(&mut session, true, Some(0));
+
+        assert_eq!(next(&mut stream).as_deref(), Some("c"));
+        assert_eq!(next(&mut stream), None);
+    }
+
+    #[test]
+    fn pauses_after_consecutive_empty_polls() {
+        let server = server(vec![vec![], vec!["a"]]);
+        let mut session = server.session();
+        let mut stream = stream(&mut session, false, Some(1));
+
+        // the empty poll before "a" does not count towards the next pause
+        assert_eq!(next(&mut stream).as_deref(), Some("a"));
+        assert_eq!(server.requests().len(), 2);
+
+        assert_eq!(next(&mut stream), None);
+        assert_eq!(server.requests().len(), 4);
+
+        assert_eq!(next(&mut stream), None);
+        assert_eq!(server.requests().len(), 6);
+    }
+
+    #[test]
+    fn backoff_grows_while_empty_and_resets_on_items() {
+        let server = server(vec![vec![], vec![], vec!["a"]]);
+        let mut session = server.session();
+        let mut stream = stream(&mut session, false, Some(0));
+        stream.backoff = ExponentialBackoff::new(MAX_WAIT_SECONDS);
+
+        assert_eq!(next(&mut stream), None);
+        assert_eq!(stream.backoff.base, 2.0);
+
+        // skips the wait set by the empty poll
+        stream.wait = None;
+        assert_eq!(next(&mut stream), None);
+        assert_eq!(stream.backoff.base, 4.0);
+
+        stream.wait = None;
+        assert_eq!(next(&mut stream).as_deref(), Some("a"));
+        assert_eq!(stream.backoff.base, 1.0);
+    }
+}
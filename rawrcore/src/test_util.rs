@@ -0,0 +1,183 @@
+// fixtures shared by the unit tests
+use serde_json::{json, Value};
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+use url::form_urlencoded;
+
+use crate::{
+    auth::{
+        authenticators::UntrustedAuthenticator,
+        authorizers::{Authorizer, BaseAuthorizer, DeviceIdAuthorizer},
+    },
+    constants,
+    requestor::Requestor,
+    sessions::{FiniteRetryStrategy, Session},
+};
+
+// an installed app authorizer that talks to `reddit_url`, or to reddit
+// itself when none is given
+pub(crate) fn authorizer(reddit_url: Option<&str>) -> Box<dyn Authorizer> {
+    let requestor = Requestor::new("rawrcore tests", reddit_url, reddit_url, None, None)
+        .ok()
+        .unwrap();
+    let authenticator = UntrustedAuthenticator::new(requestor, "client", None);
+
+    Box::new(DeviceIdAuthorizer::new(
+        BaseAuthorizer::new(Box::new(authenticator), false),
+        None,
+        None,
+    ))
+}
+
+pub(crate) fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+#[derive(Clone)]
+pub(crate) struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockRequest {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        find(&self.query, name)
+    }
+
+    // a field of a url encoded body
+    pub fn form(&self, name: &str) -> Option<String> {
+        let fields: Vec<(String, String)> = form_urlencoded::parse(self.body.as_bytes())
+            .into_owned()
+            .collect();
+
+        find(&fields, name).map(|value| value.to_string())
+    }
+}
+
+fn find<'a>(pairs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(key, _)| key.eq(name))
+        .map(|(_, value)| value.as_str())
+}
+
+// a stand-in for reddit on a local port. token requests are answered by the
+// server itself; everything else goes to the handler, which returns the
+// status and json body to answer with (null for an empty body)
+pub(crate) struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    pub fn start<F>(mut handler: F) -> Self
+    where
+        F: FnMut(&MockRequest) -> (u16, Value) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let seen = requests.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request = read_request(&mut stream);
+
+                let (status, body) = match request.path.eq(constants::ACCESS_TOKEN_PATH.as_str()) {
+                    true => (
+                        200,
+                        json!({"access_token": "token", "token_type": "bearer", "expires_in": 3600, "scope": "*"}),
+                    ),
+                    false => {
+                        let answer = handler(&request);
+                        seen.lock().unwrap().push(request);
+                        answer
+                    }
+                };
+
+                write_response(&mut stream, status, body);
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    pub fn session(&self) -> Session<FiniteRetryStrategy> {
+        Session::new(authorizer(Some(self.url.as_str())), None)
+    }
+
+    // every request but token requests, in the order they were received
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> MockRequest {
+    let mut buffer = vec![];
+    let mut chunk = [0; 4096];
+
+    let header_end = loop {
+        let read = stream.read(&mut chunk).unwrap();
+        buffer.extend_from_slice(&chunk[..read]);
+
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let content_length = head
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            match name.eq_ignore_ascii_case("content-length") {
+                true => value.trim().parse::<usize>().ok(),
+                false => None,
+            }
+        })
+        .unwrap_or_default();
+
+    while buffer.len() < header_end + content_length {
+        let read = stream.read(&mut chunk).unwrap();
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let mut request_line = head.lines().next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    MockRequest {
+        method,
+        path: path.to_string(),
+        query: form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect(),
+        body: String::from_utf8_lossy(&buffer[header_end..]).to_string(),
+    }
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: Value) {
+    let body = match body {
+        Value::Null => String::new(),
+        value => value.to_string(),
+    };
+    let response = format!(
+        "HTTP/1.1 {} Mock\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes()).unwrap();
+}
@@ -0,0 +1,247 @@
+use futures::{stream, StreamExt};
+use reqwest::{
+    multipart::{Form, Part},
+    Body,
+};
+use serde::Serialize;
+use std::{fs, path::Path, sync::Arc};
+
+use crate::{
+    errors::{RawrCoreError, RequestErrorData},
+    util,
+};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+pub const MAX_IMAGE_SIZE: u64 = 20 * 1024 * 1024;
+pub const MAX_VIDEO_SIZE: u64 = 1024 * 1024 * 1024;
+
+// called with (bytes sent, total bytes) as the file is streamed out
+pub type ProgressCallback = Arc<dyn Fn(u64, u64) + Send + Sync>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+fn sniff_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+        return Some("image/png");
+    }
+
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+
+    if bytes.len() >= 12 && bytes[0..4].eq(b"RIFF") && bytes[8..12].eq(b"WEBP") {
+        return Some("image/webp");
+    }
+
+    if bytes.len() >= 12 && bytes[4..8].eq(b"ftyp") {
+        if bytes[8..12].eq(b"qt  ") {
+            return Some("video/quicktime");
+        }
+
+        return Some("video/mp4");
+    }
+
+    None
+}
+
+#[derive(Clone)]
+pub struct UploadFile {
+    field_name: String,
+    file_name: String,
+    mime_type: String,
+    bytes: Arc<Vec<u8>>,
+    progress: Option<ProgressCallback>,
+}
+
+impl UploadFile {
+    // the MIME type is sniffed from the file contents, falling back to the
+    // file name's extension
+    pub fn from_bytes(file_name: &str, bytes: Vec<u8>) -> Result<Self, RawrCoreError> {
+        let mime_type = match sniff_mime_type(&bytes) {
+            Some(mime_type) => mime_type.to_string(),
+            None => match mime_guess::from_path(file_name).first() {
+                Some(mime_type) => mime_type.essence_str().to_string(),
+                None => {
+                    return Err(RawrCoreError::InvalidInvocation(
+                        "could not determine the MIME type of the file",
+                    ))
+                }
+            },
+        };
+
+        Ok(Self {
+            field_name: "file".to_string(),
+            file_name: file_name.to_string(),
+            mime_type,
+            bytes: Arc::new(bytes),
+            progress: None,
+        })
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, RawrCoreError> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "could not read the file to upload",
+                ))
+            }
+        };
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("file");
+
+        Self::from_bytes(file_name, bytes)
+    }
+
+    pub fn field_name(mut self, field_name: &str) -> Self {
+        self.field_name = field_name.to_string();
+        self
+    }
+
+    pub fn progress(mut self, progress: ProgressCallback) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    pub fn file_name(&self) -> &str {
+        self.file_name.as_str()
+    }
+
+    pub fn mime_type(&self) -> &str {
+        self.mime_type.as_str()
+    }
+
+    pub fn size(&self) -> u64 {
+        self.bytes.len() as u64
+    }
+
+    pub fn media_kind(&self) -> Option<MediaKind> {
+        if self.mime_type.starts_with("image/") {
+            return Some(MediaKind::Image);
+        }
+
+        if self.mime_type.starts_with("video/") {
+            return Some(MediaKind::Video);
+        }
+
+        None
+    }
+
+    // checks the file against reddit's size limits for its kind of media
+    pub fn validate_media(&self) -> Result<MediaKind, RawrCoreError> {
+        let kind = match self.media_kind() {
+            Some(kind) => kind,
+            None => {
+                return Err(RawrCoreError::InvalidInvocation(
+                    "only images and videos can be uploaded as media",
+                ))
+            }
+        };
+
+        if self.bytes.is_empty() {
+            return Err(RawrCoreError::InvalidInvocation("cannot upload an empty file"));
+        }
+
+        match kind {
+            MediaKind::Image if self.size() > MAX_IMAGE_SIZE => Err(
+                RawrCoreError::InvalidInvocation("images must be at most 20MB"),
+            ),
+            MediaKind::Video if self.size() > MAX_VIDEO_SIZE => Err(
+                RawrCoreError::InvalidInvocation("videos must be at most 1GB"),
+            ),
+            _ => Ok(kind),
+        }
+    }
+
+    pub(crate) fn to_part(&self) -> Result<Part, RawrCoreError> {
+        let total = self.size();
+        let bytes = self.bytes.clone();
+        let progress = self.progress.clone();
+        let mut sent = 0;
+
+        // the body is streamed in chunks so progress is reported as the
+        // request is written rather than all at once
+        let chunks = stream::iter((0..bytes.len()).step_by(CHUNK_SIZE)).map(move |start| {
+            let end = (start + CHUNK_SIZE).min(bytes.len());
+            let chunk = bytes[start..end].to_vec();
+
+            sent += chunk.len() as u64;
+            if let Some(progress) = &progress {
+                progress(sent, total);
+            }
+
+            Ok::<Vec<u8>, std::io::Error>(chunk)
+        });
+
+        match Part::stream_with_length(Body::wrap_stream(chunks), total)
+            .file_name(self.file_name.clone())
+            .mime_str(self.mime_type.as_str())
+        {
+            Ok(part) => Ok(part),
+            Err(e) => Err(RawrCoreError::RequestError(RequestErrorData {
+                original_error: e,
+                request: None,
+            })),
+        }
+    }
+}
+
+// anything that can be passed as the `files` of `Session::request`
+pub trait UploadFiles {
+    fn files(&self) -> Vec<&UploadFile>;
+}
+
+impl UploadFiles for () {
+    fn files(&self) -> Vec<&UploadFile> {
+        vec![]
+    }
+}
+
+impl UploadFiles for UploadFile {
+    fn files(&self) -> Vec<&UploadFile> {
+        vec![self]
+    }
+}
+
+impl UploadFiles for [UploadFile] {
+    fn files(&self) -> Vec<&UploadFile> {
+        self.iter().collect()
+    }
+}
+
+impl UploadFiles for Vec<UploadFile> {
+    fn files(&self) -> Vec<&UploadFile> {
+        self.iter().collect()
+    }
+}
+
+// form fields are written before the files, which S3-style upload
+// endpoints require
+pub(crate) fn multipart_form<D: Serialize + ?Sized>(
+    data: Option<&D>,
+    files: &[&UploadFile],
+) -> Result<Form, RawrCoreError> {
+    let mut form = Form::new();
+
+    if let Some(data) = data {
+        for (name, value) in util::form_pairs(data)? {
+            form = form.text(name, value);
+        }
+    }
+
+    for file in files {
+        form = form.part(file.field_name.clone(), file.to_part()?);
+    }
+
+    Ok(form)
+}
@@ -0,0 +1,50 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use url::form_urlencoded;
+
+use crate::errors::{BadJSONData, RawrCoreError, RedditAPIErrorData, RedditErrorItem, RedditErrorType};
+
+pub(crate) fn from_json_value<T: DeserializeOwned>(value: Value) -> Result<T, RawrCoreError> {
+    let body = value.to_string();
+
+    match serde_json::from_value::<T>(value) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(RawrCoreError::BadJSON(BadJSONData {
+            body,
+            original_error: e,
+        })),
+    }
+}
+
+// endpoints called with api_type=json wrap their result as
+// {"json": {"errors": [[type, message, field], ...], "data": {...}}}
+pub(crate) fn json_api_data(mut value: Value) -> Result<Value, RawrCoreError> {
+    let mut json = value["json"].take();
+
+    let errors: Vec<RedditErrorItem> = match json["errors"].as_array() {
+        Some(errors) => errors
+            .iter()
+            .map(|error| RedditErrorItem {
+                error_type: RedditErrorType::from(error[0].as_str().unwrap_or_default()),
+                message: error[1].as_str().unwrap_or_default().to_string(),
+                field: error[2].as_str().map(|field| field.to_string()),
+            })
+            .collect(),
+        None => vec![],
+    };
+
+    if !errors.is_empty() {
+        return Err(RawrCoreError::RedditAPIError(RedditAPIErrorData { errors }));
+    }
+
+    Ok(json["data"].take())
+}
+
+pub(crate) fn form_pairs<D: Serialize + ?Sized>(data: &D) -> Result<Vec<(String, String)>, RawrCoreError> {
+    let encoded = match serde_urlencoded::to_string(data) {
+        Ok(encoded) => encoded,
+        Err(_) => return Err(RawrCoreError::InvalidInvocation("form data could not be encoded")),
+    };
+
+    Ok(form_urlencoded::parse(encoded.as_bytes()).into_owned().collect())
+}